@@ -0,0 +1,118 @@
+use std::time::{Duration, Instant};
+
+use crate::{input::InputError, solution::Solution};
+
+pub const DEFAULT_ITERATIONS: u32 = 10;
+pub const DEFAULT_WARMUPS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Summarizes a non-empty set of timings.
+    pub fn of(samples: &[Duration]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+        let median = if n % 2 == 0 {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        let mean = sorted.iter().sum::<Duration>() / n as u32;
+        let mean_secs = mean.as_secs_f64();
+        let variance = sorted
+            .iter()
+            .map(|sample| (sample.as_secs_f64() - mean_secs).powi(2))
+            .sum::<f64>()
+            / n as f64;
+        Stats {
+            min: sorted[0],
+            median,
+            mean,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartTimings {
+    pub part: u32,
+    pub total: Stats,
+    /// `None` when the day does not expose its parse phase.
+    pub parse: Option<Stats>,
+    pub solve: Option<Stats>,
+}
+
+/// Times one part of `solution` on an input that has already been read.
+///
+/// Each iteration runs the parse phase on its own and then the whole part; the solve time is the
+/// difference, since the parsed form of a day is not visible outside its module.
+pub fn bench_part(
+    solution: &dyn Solution,
+    part: u32,
+    input: &str,
+    iterations: u32,
+    warmups: u32,
+) -> Result<PartTimings, InputError> {
+    for _ in 0..warmups {
+        solution.part(part, input).unwrap()?;
+    }
+    let mut totals = Vec::new();
+    let mut parses = Vec::new();
+    for _ in 0..iterations.max(1) {
+        let start = Instant::now();
+        if let Some(parsed) = solution.parse(part, input) {
+            parsed?;
+            parses.push(start.elapsed());
+        }
+        let start = Instant::now();
+        solution.part(part, input).unwrap()?;
+        totals.push(start.elapsed());
+    }
+    let solves: Vec<Duration> = totals
+        .iter()
+        .zip(&parses)
+        .map(|(total, parse)| total.saturating_sub(*parse))
+        .collect();
+    Ok(PartTimings {
+        part,
+        total: Stats::of(&totals),
+        parse: (!parses.is_empty()).then(|| Stats::of(&parses)),
+        solve: (!solves.is_empty()).then(|| Stats::of(&solves)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{day_01::Day01, day_06::Day06};
+
+    #[test]
+    fn stats_of_samples() {
+        let samples: Vec<Duration> = [4, 1, 3, 2].map(Duration::from_millis).to_vec();
+        let stats = Stats::of(&samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.stddev.as_micros(), 1118);
+    }
+
+    #[test]
+    fn times_both_phases() {
+        let input = "Time:      7  15   30
+Distance:  9  40  200
+";
+        let timings = bench_part(&Day06, 2, input, 5, 1).unwrap();
+        assert_eq!(timings.part, 2);
+        assert!(timings.parse.is_some() && timings.solve.is_some());
+        assert!(bench_part(&Day06, 1, "Time: x\n", 1, 0).is_err());
+        let timings = bench_part(&Day01, 1, "1abc2\n", 1, 0).unwrap();
+        assert_eq!((timings.parse, timings.solve), (None, None));
+    }
+}
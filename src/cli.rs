@@ -0,0 +1,276 @@
+use std::path::PathBuf;
+
+use crate::bench;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Cache,
+    Stdin,
+    File(PathBuf),
+    Example(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selection {
+    All,
+    Day(u32, Option<u32>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Run {
+        selection: Selection,
+        input: InputSource,
+        format: Format,
+    },
+    Verify,
+    Bench {
+        day: u32,
+        part: Option<u32>,
+        iterations: u32,
+        warmups: u32,
+    },
+    Scaffold {
+        day: u32,
+    },
+    Help,
+}
+
+pub const USAGE: &str = "usage:
+    advent_of_code_2023 run --day N [--part 1|2] [--input PATH|- | --example NAME] [--format text|json|csv]
+    advent_of_code_2023 run --all [--format text|json|csv]
+    advent_of_code_2023 verify
+    advent_of_code_2023 bench --day N [--part 1|2] [--iterations K] [--warmup W]
+    advent_of_code_2023 scaffold --day N";
+
+fn parse_number(flag: &str, value: Option<String>) -> Result<u32, String> {
+    let value = value.ok_or_else(|| format!("{} expects a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a number, got `{}`", flag, value))
+}
+
+fn parse_part(value: Option<String>) -> Result<u32, String> {
+    let part = parse_number("--part", value)?;
+    if part != 1 && part != 2 {
+        return Err(format!("--part must be 1 or 2, got {}", part));
+    }
+    Ok(part)
+}
+
+fn parse_run(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut day: Option<u32> = None;
+    let mut part: Option<u32> = None;
+    let mut all = false;
+    let mut input = InputSource::Cache;
+    let mut format = Format::Text;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number("--day", args.next())?),
+            "--part" => part = Some(parse_part(args.next())?),
+            "--all" => all = true,
+            "--input" | "--example" if input != InputSource::Cache => {
+                return Err("--input and --example can only be given once between them".to_string())
+            }
+            "--example" => {
+                input = InputSource::Example(
+                    args.next()
+                        .ok_or("--example expects the name of a file in examples/NN")?,
+                )
+            }
+            "--input" => {
+                input = match args.next().as_deref() {
+                    Some("-") => InputSource::Stdin,
+                    Some(path) => InputSource::File(PathBuf::from(path)),
+                    None => return Err("--input expects a path or `-`".to_string()),
+                }
+            }
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some("csv") => Format::Csv,
+                    _ => return Err("--format expects `text`, `json` or `csv`".to_string()),
+                }
+            }
+            _ => return Err(format!("unrecognized argument `{}`", arg)),
+        }
+    }
+    let selection = match (all, day) {
+        (true, None) if part.is_none() && input == InputSource::Cache => Selection::All,
+        (true, _) => {
+            return Err("--all cannot be combined with --day, --part or --input".to_string())
+        }
+        (false, Some(day)) => Selection::Day(day, part),
+        (false, None) => return Err("run expects --day N or --all".to_string()),
+    };
+    Ok(Command::Run {
+        selection,
+        input,
+        format,
+    })
+}
+
+fn parse_bench(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut day: Option<u32> = None;
+    let mut part: Option<u32> = None;
+    let mut iterations = bench::DEFAULT_ITERATIONS;
+    let mut warmups = bench::DEFAULT_WARMUPS;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number("--day", args.next())?),
+            "--part" => part = Some(parse_part(args.next())?),
+            "--iterations" => iterations = parse_number("--iterations", args.next())?,
+            "--warmup" => warmups = parse_number("--warmup", args.next())?,
+            _ => return Err(format!("unrecognized argument `{}`", arg)),
+        }
+    }
+    if iterations == 0 {
+        return Err("--iterations must be at least 1".to_string());
+    }
+    Ok(Command::Bench {
+        day: day.ok_or("bench expects --day N")?,
+        part,
+        iterations,
+        warmups,
+    })
+}
+
+fn parse_scaffold(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    match (args.next().as_deref(), args.next()) {
+        (Some("--day"), value) => {
+            let day = parse_number("--day", value)?;
+            match args.next() {
+                None => Ok(Command::Scaffold { day }),
+                Some(arg) => Err(format!("unrecognized argument `{}`", arg)),
+            }
+        }
+        _ => Err("scaffold expects --day N".to_string()),
+    }
+}
+
+pub fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    match args.next().as_deref() {
+        Some("run") => parse_run(args),
+        Some("bench") => parse_bench(args),
+        Some("scaffold") => parse_scaffold(args),
+        Some("verify") => match args.next() {
+            None => Ok(Command::Verify),
+            Some(arg) => Err(format!("verify takes no arguments, got `{}`", arg)),
+        },
+        Some("help") | Some("--help") | Some("-h") | None => Ok(Command::Help),
+        Some(other) => Err(format!("unrecognized command `{}`", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, String> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn run_day_and_part() {
+        assert_eq!(
+            parse("run --day 17 --part 2 --input -"),
+            Ok(Command::Run {
+                selection: Selection::Day(17, Some(2)),
+                input: InputSource::Stdin,
+                format: Format::Text,
+            })
+        );
+    }
+
+    #[test]
+    fn run_example() {
+        assert_eq!(
+            parse("run --day 10 --part 2 --example part2_b"),
+            Ok(Command::Run {
+                selection: Selection::Day(10, Some(2)),
+                input: InputSource::Example("part2_b".to_string()),
+                format: Format::Text,
+            })
+        );
+    }
+
+    #[test]
+    fn run_all() {
+        assert_eq!(
+            parse("run --all"),
+            Ok(Command::Run {
+                selection: Selection::All,
+                input: InputSource::Cache,
+                format: Format::Text,
+            })
+        );
+        assert!(parse("run --all --day 3").is_err());
+        assert_eq!(
+            parse("run --all --format json"),
+            Ok(Command::Run {
+                selection: Selection::All,
+                input: InputSource::Cache,
+                format: Format::Json,
+            })
+        );
+        assert!(parse("run --all --format xml").is_err());
+    }
+
+    #[test]
+    fn verify() {
+        assert_eq!(parse("verify"), Ok(Command::Verify));
+    }
+
+    #[test]
+    fn bench_defaults() {
+        assert_eq!(
+            parse("bench --day 16"),
+            Ok(Command::Bench {
+                day: 16,
+                part: None,
+                iterations: bench::DEFAULT_ITERATIONS,
+                warmups: bench::DEFAULT_WARMUPS,
+            })
+        );
+        assert_eq!(
+            parse("bench --day 5 --part 2 --iterations 50 --warmup 0"),
+            Ok(Command::Bench {
+                day: 5,
+                part: Some(2),
+                iterations: 50,
+                warmups: 0,
+            })
+        );
+        assert!(parse("bench --iterations 5").is_err());
+        assert!(parse("bench --day 5 --iterations 0").is_err());
+    }
+
+    #[test]
+    fn scaffold() {
+        assert_eq!(
+            parse("scaffold --day 18"),
+            Ok(Command::Scaffold { day: 18 })
+        );
+        assert!(parse("scaffold").is_err());
+        assert!(parse("scaffold --day 18 --part 1").is_err());
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(parse("run").is_err());
+        assert!(parse("run --day seventeen").is_err());
+        assert!(parse("run --day 1 --part 3").is_err());
+        assert!(parse("fly").is_err());
+        assert!(parse("run --day 10 --example a --input -").is_err());
+        assert!(parse("run --all --example a").is_err());
+        assert!(parse("verify --day 1").is_err());
+    }
+}
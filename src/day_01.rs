@@ -1,28 +1,48 @@
 use advent_of_code::parse::{parsers, Parser};
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> u32 {
+use crate::{
+    input::InputError,
+    solution::{Answer, Solution},
+};
+
+const EXPECTED: &str = "a letter or digit";
+
+/// The first and last of `digits` as a two-digit number, or an error on line `line_idx` if it has none.
+fn calibration_value(
+    input: &str,
+    line_idx: usize,
+    digits: &[u32],
+    expected: &str,
+) -> Result<u32, InputError> {
+    match (digits.first(), digits.last()) {
+        (Some(first), Some(last)) => Ok(first * 10 + last),
+        _ => Err(InputError::at(input, line_idx + 1, 1, expected)),
+    }
+}
+
+pub fn part1(input: &str) -> Result<u32, InputError> {
     parsers::chars(|c| c.is_alphanumeric())
         .many()
         .many_lines("\n")
         .parse(input)
         .finish()
-        .expect("Failed to parse input")
-        .map(|line| {
+        .map_err(|_| InputError::invalid_char(input, EXPECTED, char::is_alphanumeric))?
+        .enumerate()
+        .map(|(idx, line)| {
             let digits: Vec<u32> = line.filter_map(|digit| digit.to_digit(10)).collect();
-            digits.first().unwrap() * 10 + digits.last().unwrap()
+            calibration_value(input, idx, &digits, "a line with a digit")
         })
         .sum()
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> Result<u32, InputError> {
     parsers::many_chars(|c| c.is_alphanumeric())
         .many_lines("\n")
         .parse(input)
         .finish()
-        .expect("Failed to parse input")
-        .map(|s| {
+        .map_err(|_| InputError::invalid_char(input, EXPECTED, char::is_alphanumeric))?
+        .enumerate()
+        .map(|(line_idx, s)| {
             let mut digits_at: Vec<Option<u32>> = vec![None; s.len()];
             s.bytes().enumerate().for_each(|(idx, d)| {
                 (d as char)
@@ -51,99 +71,46 @@ pub fn part2(input: &str) -> u32 {
                     .for_each(|&idx| digits_at[idx] = Some(*digit));
             });
             let digits: Vec<u32> = digits_at.iter().filter_map(|&d| d).collect();
-            digits.first().unwrap() * 10 + digits.last().unwrap()
+            calibration_value(
+                input,
+                line_idx,
+                &digits,
+                "a line with a digit or a spelled-out digit",
+            )
         })
         .sum()
 }
 
-#[allow(dead_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const DAY: Day = Day::Day01;
+pub struct Day01;
 
-    #[test]
-    fn part1_example() {
-        assert_eq!(
-            part1(
-                "1abc2
-pqr3stu8vwx
-a1b2c3d4e5f
-treb7uchet
-"
-            ),
-            142
-        );
+impl Solution for Day01 {
+    fn name(&self) -> &'static str {
+        "Trebuchet?!"
     }
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(
-            part2(
-                "two1nine
-eightwothree
-abcone2threexyz
-xtwone3four
-4nineeightseven2
-zoneight234
-7pqrstsixteen
-"
-            ),
-            281
-        );
+    fn day(&self) -> u32 {
+        1
     }
 
-    #[test]
-    fn part1_test() {
-        assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            56397
-        );
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
     }
 
-    #[test]
-    fn part2_test() {
-        assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            55701
-        );
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
     }
+}
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
-    }
+#[allow(dead_code)]
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    #[test]
+    fn line_without_digits() {
+        let error = part1("1abc2\nabc\n").unwrap_err();
+        assert_eq!((error.line, error.snippet.as_str()), (2, "abc"));
+        assert_eq!(part2("1abc2\nabc\n").unwrap_err().line, 2);
+        assert_eq!(part2("1abc2\nabcone\n"), Ok(12 + 11));
     }
 }
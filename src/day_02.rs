@@ -2,6 +2,11 @@ use std::collections::BTreeMap;
 
 use advent_of_code::parse::{parsers, Parser};
 
+use crate::{
+    input::{parse_lines, InputError},
+    solution::{Answer, Solution},
+};
+
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 enum Color {
     Red,
@@ -11,38 +16,52 @@ enum Color {
 
 fn parse(
     input: &str,
-) -> impl Iterator<
-    Item = (
-        u32,
-        impl Iterator<Item = impl Iterator<Item = (u32, Color)>>,
-    ),
+) -> Result<
+    impl Iterator<
+        Item = (
+            u32,
+            impl Iterator<Item = impl Iterator<Item = (u32, Color)>>,
+        ),
+    >,
+    InputError,
 > {
-    parsers::tag("Game ")
-        .ignore_and_then(parsers::number())
-        .skip_tag(": ")
-        .and_then(
-            parsers::number()
-                .skip_tag(" ")
-                .and_then(
-                    parsers::tag_replace("blue", Color::Blue)
-                        .or(parsers::tag_replace("green", Color::Green))
-                        .or(parsers::tag_replace("red", Color::Red)),
-                )
-                .list(", ")
-                .list("; "),
-        )
-        .many_lines("\n")
-        .parse(input)
-        .finish()
-        .expect("Failed to parse input")
+    let games = parse_lines(
+        input,
+        "a game like `Game 1: 3 blue, 4 red; 2 green`",
+        |line| {
+            let (id, rounds) = line.tag("Game ")?.split_once(": ")?;
+            let rounds = rounds
+                .split("; ")
+                .map(|round| {
+                    round
+                        .split(", ")
+                        .map(|cubes| {
+                            let (count, color) = cubes.split_once(" ")?;
+                            Ok((
+                                count.parse(parsers::number())?,
+                                color.parse(
+                                    parsers::tag_replace("blue", Color::Blue)
+                                        .or(parsers::tag_replace("green", Color::Green))
+                                        .or(parsers::tag_replace("red", Color::Red)),
+                                )?,
+                            ))
+                        })
+                        .collect::<Result<Vec<(u32, Color)>, usize>>()
+                })
+                .collect::<Result<Vec<_>, usize>>()?;
+            Ok((id.parse(parsers::number())?, rounds))
+        },
+    )?;
+    Ok(games
+        .into_iter()
+        .map(|(id, rounds)| (id, rounds.into_iter().map(Vec::into_iter))))
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> u32 {
+pub fn part1(input: &str) -> Result<u32, InputError> {
     let desired_maxima: BTreeMap<Color, u32> =
         BTreeMap::from([(Color::Red, 12), (Color::Green, 13), (Color::Blue, 14)]);
 
-    parse(input)
+    Ok(parse(input)?
         .filter_map(|(id, mut game)| {
             if game.all(|mut round| {
                 round.all(|(count, color)| desired_maxima.get(&color).unwrap_or(&0) >= &count)
@@ -52,12 +71,11 @@ pub fn part1(input: &str) -> u32 {
                 None
             }
         })
-        .sum()
+        .sum())
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> u32 {
-    parse(input)
+pub fn part2(input: &str) -> Result<u32, InputError> {
+    Ok(parse(input)?
         .map(|(_, game)| {
             let mut minima = BTreeMap::from([(Color::Red, 0), (Color::Green, 0), (Color::Blue, 0)]);
             for round in game {
@@ -67,82 +85,47 @@ pub fn part2(input: &str) -> u32 {
             }
             minima.values().product::<u32>()
         })
-        .sum()
+        .sum())
 }
 
-#[allow(dead_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const EXAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
-";
-    const DAY: Day = Day::Day02;
+pub struct Day02;
 
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 8);
+impl Solution for Day02 {
+    fn name(&self) -> &'static str {
+        "Cube Conundrum"
     }
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(EXAMPLE), 2286);
+    fn day(&self) -> u32 {
+        2
     }
 
-    #[test]
-    fn part1_test() {
-        assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            1931
-        );
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
     }
 
-    #[test]
-    fn part2_test() {
-        assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            83105
-        );
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
     }
+}
+
+#[allow(dead_code)]
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    #[test]
+    fn malformed_game() {
+        let error = part1(
+            "Game 1: 3 blue, 4 red
+Game 2: 1 purple
+",
+        )
+        .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 11));
+        assert_eq!(error.snippet, "purple");
     }
 }
@@ -5,19 +5,28 @@ use advent_of_code::{
 
 use std::collections::{HashMap, HashSet};
 
-fn parse(input: &str) -> Vec<Vec<char>> {
-    parsers::many_chars(|c| c != '\n')
-        .map(|s| s.bytes().map(|b| b as char).collect::<Vec<char>>())
+use crate::{
+    input::InputError,
+    solution::{Answer, Solution},
+};
+
+const EXPECTED: &str = "a digit, `.` or a symbol";
+
+fn parse(input: &str) -> Result<Grid<char>, InputError> {
+    let rows = parsers::chars(|c| c.is_ascii_graphic())
+        .many()
+        .map(|row| row.collect::<Vec<char>>())
         .many_lines("\n")
         .parse(input)
         .finish()
-        .expect("Failed to parse input")
-        .collect()
+        .map_err(|_| InputError::invalid_char(input, EXPECTED, |c| c.is_ascii_graphic()))?
+        .collect();
+    Grid::of_vec_of_vecs(rows)
+        .map_err(|_| InputError::in_grid(input, EXPECTED, |c| c.is_ascii_graphic()))
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> u32 {
-    let diagram: Grid<char> = Grid::of_vec_of_vecs(parse(input)).unwrap();
+pub fn part1(input: &str) -> Result<u32, InputError> {
+    let diagram: Grid<char> = parse(input)?;
     let dimensions = diagram.dimensions();
     let mut sum = 0;
     for row_start in GridPoint::new(0_usize, 0).traverse_by(SOUTH, dimensions) {
@@ -45,12 +54,11 @@ pub fn part1(input: &str) -> u32 {
             sum += current_number;
         }
     }
-    sum
+    Ok(sum)
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> u32 {
-    let diagram: Grid<char> = Grid::of_vec_of_vecs(parse(input)).unwrap();
+pub fn part2(input: &str) -> Result<u32, InputError> {
+    let diagram: Grid<char> = parse(input)?;
     let mut gears: HashMap<GridPoint<usize>, Vec<u32>> = HashMap::new();
     let dimensions = diagram.dimensions();
     for row_start in GridPoint::new(0_usize, 0).traverse_by(SOUTH, dimensions) {
@@ -79,7 +87,7 @@ pub fn part2(input: &str) -> u32 {
             gears.entry(gear).or_default().push(current_number);
         }
     }
-    gears
+    Ok(gears
         .values()
         .into_iter()
         .map(|part_numbers| {
@@ -89,87 +97,29 @@ pub fn part2(input: &str) -> u32 {
                 0
             }
         })
-        .sum()
+        .sum())
 }
 
-#[allow(dead_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const EXAMPLE: &str = "...467..114..
-......*......
-.....35..633.
-.........#...
-...617*......
-........+.58.
-.....592.....
-.........755.
-......$.*....
-....664.598..
-";
-    const DAY: Day = Day::Day03;
-
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 4361);
-    }
+pub struct Day03;
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(EXAMPLE), 467835);
+impl Solution for Day03 {
+    fn name(&self) -> &'static str {
+        "Gear Ratios"
     }
 
-    #[test]
-    fn part1_test() {
-        assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            520019
-        );
+    fn day(&self) -> u32 {
+        3
     }
 
-    #[test]
-    fn part2_test() {
-        assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            75519888
-        );
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
     }
 }
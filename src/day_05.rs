@@ -1,246 +1,428 @@
 use advent_of_code::{
     interval::{DisjointIntervalUnion, Interval, IntervalBound},
-    parse::{parsers, Parser},
+    parse::parsers,
 };
 
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, iter};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct RangeMapElement {
-    from: Interval<i64>,
-    adjustment: i64,
+use crate::{
+    input::{Field, InputError, InputLines},
+    solution::{Answer, Solution},
+};
+
+/// A map on `i64` that moves each of a run of consecutive segments by its own fixed amount. Every
+/// almanac map is one of these, with a shift of 0 for the values outside its ranges, and so is any
+/// chain of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiecewiseShift {
+    /// The start of each segment and its shift. The first segment starts at `i64::MIN` and each one
+    /// ends just before the next; neighbouring segments never have the same shift. Shifts are
+    /// `i128` because the difference of two `i64`s need not fit in one.
+    shifts: BTreeMap<i64, i128>,
 }
 
-impl RangeMapElement {
-    fn contains_from(self, n: i64) -> bool {
-        self.from.contains(&n)
+/// `n`, or the nearest end of `i64` if it lies beyond.
+fn clamp(n: i128) -> i64 {
+    n.clamp(i64::MIN.into(), i64::MAX.into()) as i64
+}
+
+#[allow(dead_code)]
+impl PiecewiseShift {
+    pub fn identity() -> Self {
+        Self {
+            shifts: BTreeMap::from([(i64::MIN, 0)]),
+        }
     }
 
-    fn convert(self, n: i64) -> i64 {
-        if self.contains_from(n) {
-            n + self.adjustment
-        } else {
-            n
+    /// Builds the map from almanac lines `(destination, source, length)`. Where two source ranges
+    /// overlap, the later line wins; ranges of no length are ignored.
+    pub fn from_ranges(ranges: impl IntoIterator<Item = (i64, i64, i64)>) -> Self {
+        let mut map = Self::identity();
+        for (destination, source, length) in ranges {
+            if length <= 0 {
+                continue;
+            }
+            // A range that runs to the end of `i64` has nothing after it to resume.
+            let end = i64::try_from(i128::from(source) + i128::from(length)).ok();
+            let resume = end.map(|end| map.shift_at(end));
+            let covered: Vec<i64> = map
+                .shifts
+                .range(source..=end.unwrap_or(i64::MAX))
+                .map(|(&k, _)| k)
+                .collect();
+            for key in covered {
+                map.shifts.remove(&key);
+            }
+            map.shifts
+                .insert(source, i128::from(destination) - i128::from(source));
+            if let (Some(end), Some(resume)) = (end, resume) {
+                map.shifts.insert(end, resume);
+            }
         }
+        map.merge_segments();
+        map
     }
-}
 
-#[derive(Debug)]
-struct RangeMap {
-    ranges: BTreeMap<i64, RangeMapElement>,
-}
+    fn of_segments(shifts: BTreeMap<i64, i128>) -> Self {
+        let mut map = Self { shifts };
+        map.merge_segments();
+        map
+    }
 
-impl RangeMap {
-    fn convert(&self, n: i64) -> i64 {
-        self.ranges
-            .range(..=n)
-            .last()
-            .map(|(_, element)| element.convert(n))
-            .unwrap_or(n)
+    fn merge_segments(&mut self) {
+        let mut previous = None;
+        self.shifts.retain(|_, &mut shift| {
+            let keep = previous != Some(shift);
+            previous = Some(shift);
+            keep
+        });
     }
 
-    fn convert_range(&self, range: Interval<i64>) -> DisjointIntervalUnion<i64> {
-        let unchanged = self.ranges.iter().fold(
-            DisjointIntervalUnion::singleton(range),
-            |unchanged, (_, range)| unchanged - range.from,
-        );
+    fn shift_at(&self, n: i64) -> i128 {
+        *self.shifts.range(..=n).next_back().unwrap().1
+    }
+
+    /// Where `n` maps to, saturating at the ends of `i64`.
+    pub fn apply(&self, n: i64) -> i64 {
+        clamp(i128::from(n) + self.shift_at(n))
+    }
+
+    /// The segments that meet `lo..`, in order, as inclusive `(start, end, shift)`.
+    fn segments_from(&self, lo: i64) -> impl Iterator<Item = (i64, i64, i128)> + '_ {
+        let first = *self.shifts.range(..=lo).next_back().unwrap().0;
+        let ends = self
+            .shifts
+            .range(first..)
+            .skip(1)
+            .map(|(&next, _)| next - 1)
+            .chain(iter::once(i64::MAX));
+        self.shifts
+            .range(first..)
+            .zip(ends)
+            .map(|((&start, &shift), end)| (start, end, shift))
+    }
+
+    /// The map from the first segment to the last as inclusive `(start, end, shift)`.
+    pub fn segments(&self) -> impl Iterator<Item = (i64, i64, i128)> + '_ {
+        self.segments_from(i64::MIN)
+    }
 
-        self.ranges
-            .iter()
-            .filter_map(|(_, element)| {
-                Some(range.intersection(&element.from)? + element.adjustment)
+    /// The map that applies `self` and then `next`, flattened into one set of segments, so that
+    /// `self.then(next)?.apply(n) == next.apply(self.apply(n))`. `None` if `next` moves values that
+    /// `self` saturated at an end of `i64` back inside it: they would all land on one value, which
+    /// no shift can express.
+    pub fn then(&self, next: &Self) -> Option<Self> {
+        let mut shifts = BTreeMap::new();
+        for (start, end, shift) in self.segments() {
+            let (lo, hi) = (i128::from(start) + shift, i128::from(end) + shift);
+            if (hi > i64::MAX.into() && next.shift_at(i64::MAX) < 0)
+                || (lo < i64::MIN.into() && next.shift_at(i64::MIN) > 0)
+            {
+                return None;
+            }
+            let (lo, hi) = (clamp(lo), clamp(hi));
+            for (next_start, _, next_shift) in next.segments_from(lo).take_while(|s| s.0 <= hi) {
+                // Past `lo`, `next_start` is the image of a value inside this segment.
+                let piece_start = if next_start <= lo {
+                    start
+                } else {
+                    (i128::from(next_start) - shift) as i64
+                };
+                shifts.insert(piece_start, shift + next_shift);
+            }
+        }
+        Some(Self::of_segments(shifts))
+    }
+
+    /// The map that undoes `self`, or `None` if two values map to the same one, some value is not
+    /// the image of any, or some value maps beyond `i64`.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut images: Vec<(i64, i64, i128)> = self
+            .segments()
+            .map(|(start, end, shift)| {
+                Some((
+                    i64::try_from(i128::from(start) + shift).ok()?,
+                    i64::try_from(i128::from(end) + shift).ok()?,
+                    -shift,
+                ))
             })
-            .collect::<DisjointIntervalUnion<i64>>()
-            | unchanged
+            .collect::<Option<_>>()?;
+        images.sort_unstable();
+        let mut expected = Some(i64::MIN);
+        for &(start, end, _) in &images {
+            if expected != Some(start) {
+                return None;
+            }
+            expected = end.checked_add(1);
+        }
+        if expected.is_some() {
+            return None;
+        }
+        Some(Self::of_segments(
+            images
+                .into_iter()
+                .map(|(start, _, shift)| (start, shift))
+                .collect(),
+        ))
+    }
+
+    /// Every value of `i64` that some value of `lo..=hi` maps to; empty if `lo > hi`.
+    pub fn image(&self, lo: i64, hi: i64) -> DisjointIntervalUnion<i64> {
+        self.segments_from(lo)
+            .take_while(|&(start, _, _)| lo <= hi && start <= hi)
+            .filter_map(|(start, end, shift)| {
+                let from = i128::from(start.max(lo)) + shift;
+                let to = i128::from(end.min(hi)) + shift;
+                (to >= i64::MIN.into() && from <= i64::MAX.into()).then(|| {
+                    Interval::new(
+                        IntervalBound::Inclusive(clamp(from)),
+                        IntervalBound::Inclusive(clamp(to)),
+                    )
+                })
+            })
+            .collect()
     }
 }
 
 fn parse(
     input: &str,
-) -> (
-    impl Iterator<Item = i64>,
-    impl Iterator<Item = (String, RangeMap)>,
-) {
-    parsers::tag("seeds: ")
-        .ignore_and_then(parsers::number::<i64>().list(" "))
-        .skip_tag("\n\n")
-        .and_then(
-            parsers::many_chars(|c| c != ' ')
-                .skip_tag(" map:\n")
-                .and_then(
-                    parsers::number::<i64>()
-                        .skip_tag(" ")
-                        .and_then(parsers::number::<i64>().skip_tag(" "))
-                        .and_then(parsers::number::<i64>())
-                        .map(|((a, b), c)| {
-                            (
-                                b,
-                                RangeMapElement {
-                                    from: Interval::new(
-                                        IntervalBound::Inclusive(b),
-                                        IntervalBound::Inclusive(b + c - 1),
-                                    ),
-                                    adjustment: a - b,
-                                },
-                            )
-                        })
-                        .many_lines("\n")
-                        .map(|elements| RangeMap {
-                            ranges: elements.collect(),
-                        }),
-                )
-                .list("\n"),
-        )
-        .parse(input)
-        .finish()
-        .expect("Failed to parse input")
+) -> Result<
+    (
+        impl Iterator<Item = i64>,
+        impl Iterator<Item = (String, PiecewiseShift)>,
+    ),
+    InputError,
+> {
+    let mut lines = InputLines::new(input);
+    let seeds = lines.next_line("a seed list like `seeds: 79 14 55 13`", |line| {
+        line.tag("seeds: ")?
+            .split(" ")
+            .map(|n| n.parse(parsers::number::<i64>()))
+            .collect::<Result<Vec<i64>, usize>>()
+    })?;
+    lines.blank_line()?;
+    let mut maps = Vec::new();
+    while !lines.is_empty() {
+        let name = lines.next_line("a map header like `seed-to-soil map:`", |line| {
+            let (name, header) = line.split_once(" ")?;
+            header.tag("map:")?.empty()?;
+            name.parse(parsers::many_chars(|c| c != ' '))
+        })?;
+        let ranges = lines.until_blank("a range like `50 98 2`", |line| {
+            let (destination, rest) = line.split_once(" ")?;
+            let (source, length) = rest.split_once(" ")?;
+            let number = |field: Field<'_>| field.parse(parsers::number::<i64>());
+            Ok((number(destination)?, number(source)?, number(length)?))
+        })?;
+        maps.push((name, PiecewiseShift::from_ranges(ranges)));
+    }
+    Ok((seeds.into_iter(), maps.into_iter()))
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> i64 {
-    let (seeds, maps) = parse(input);
-    let maps: Vec<RangeMap> = maps.map(|(_, range_map)| range_map).collect();
+/// Parses the seeds and collapses every map of the almanac into one, from seed to location.
+pub fn almanac(input: &str) -> Result<(Vec<i64>, PiecewiseShift), InputError> {
+    let (seeds, maps) = parse(input)?;
+    let mut map = PiecewiseShift::identity();
+    for (name, next) in maps {
+        map = map.then(&next).ok_or_else(|| {
+            InputError::at(
+                input,
+                1,
+                1,
+                &format!(
+                    "a `{}` map that keeps values saturated at the ends of i64 there",
+                    name
+                ),
+            )
+        })?;
+    }
+    Ok((seeds.collect(), map))
+}
+
+pub fn part1(input: &str) -> Result<i64, InputError> {
+    let (seeds, map) = almanac(input)?;
     seeds
-        .map(|seed| maps.iter().fold(seed, |value, map| map.convert(value)))
+        .into_iter()
+        .map(|seed| map.apply(seed))
         .min()
-        .unwrap()
+        .ok_or_else(|| InputError::at(input, 1, 1, "at least one seed"))
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> i64 {
-    let (seeds, maps) = parse(input);
-    let maps: Vec<RangeMap> = maps.map(|(_, range_map)| range_map).collect();
-    let seeds: Vec<i64> = seeds.collect();
-    let mut seed_ranges: Vec<Interval<i64>> = Vec::new();
-    for idx in (0..seeds.len()).step_by(2) {
-        seed_ranges.push(Interval::new(
-            IntervalBound::Inclusive(seeds[idx]),
-            IntervalBound::Inclusive(seeds[idx] + seeds[idx + 1] - 1),
+pub fn part2(input: &str) -> Result<i64, InputError> {
+    let (seeds, map) = almanac(input)?;
+    if seeds.len() % 2 != 0 {
+        return Err(InputError::at(
+            input,
+            1,
+            1,
+            "seed ranges as pairs of a start and a length",
         ));
     }
-
-    seed_ranges
-        .iter()
-        .map(|&seed_range| {
-            maps.iter()
-                .fold(
-                    DisjointIntervalUnion::singleton(seed_range),
-                    |ranges: DisjointIntervalUnion<i64>, map| {
-                        ranges
-                            .into_iter()
-                            .map(|range| map.convert_range(range))
-                            .collect()
-                    },
-                )
+    seeds
+        .chunks_exact(2)
+        .filter(|range| range[1] > 0)
+        .filter_map(|range| {
+            map.image(range[0], range[0].saturating_add(range[1] - 1))
                 .lower_bound()
                 .copied()
-                .unwrap()
         })
         .min()
-        .unwrap()
+        .ok_or_else(|| InputError::at(input, 1, 1, "at least one non-empty seed range"))
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    fn name(&self) -> &'static str {
+        "If You Give A Seed A Fertilizer"
+    }
+
+    fn day(&self) -> u32 {
+        5
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
+    }
+
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
+    }
 }
 
 #[allow(dead_code)]
 #[cfg(test)]
 mod tests {
     use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
 
-    const EXAMPLE: &str = "seeds: 79 14 55 13
+    #[test]
+    fn malformed_range() {
+        let error = part1(
+            "seeds: 79 14
 
 seed-to-soil map:
 50 98 2
-52 50 48
-
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
-
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
-
-water-to-light map:
-88 18 7
-18 25 70
-
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
-
-temperature-to-humidity map:
-0 69 1
-1 0 69
-
-humidity-to-location map:
-60 56 37
-56 93 4
-";
-    const DAY: Day = Day::Day05;
+52 50
+",
+        )
+        .unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.snippet.as_str()),
+            (5, 6, "")
+        );
+        let error = part2("seeds: 79 14 55\n\nseed-to-soil map:\n50 98 2\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+        let error = part1("seeds: 79 x\n\nseed-to-soil map:\n50 98 2\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 11));
+    }
 
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 35);
+    fn inclusive(lo: i64, hi: i64) -> Interval<i64> {
+        Interval::new(IntervalBound::Inclusive(lo), IntervalBound::Inclusive(hi))
     }
 
     #[test]
-    fn part2_example() {
-        assert_eq!(part2(EXAMPLE), 46);
+    fn collapsed_almanac_matches_each_map() {
+        let input = include_str!("../examples/05/example.in");
+        let (_, maps) = parse(input).unwrap();
+        let maps: Vec<PiecewiseShift> = maps.map(|(_, map)| map).collect();
+        let (_, almanac) = almanac(input).unwrap();
+        for seed in -5..120 {
+            let location = maps.iter().fold(seed, |value, map| map.apply(value));
+            assert_eq!(almanac.apply(seed), location, "seed {}", seed);
+        }
+        assert!(almanac.segments().count() < maps.iter().map(|map| map.segments().count()).sum());
     }
 
     #[test]
-    fn part1_test() {
+    fn inverse_and_image() {
+        let seed_to_soil = PiecewiseShift::from_ranges([(50, 98, 2), (52, 50, 48)]);
+        let soil_to_seed = seed_to_soil.inverse().unwrap();
+        for seed in -5..120 {
+            assert_eq!(soil_to_seed.apply(seed_to_soil.apply(seed)), seed);
+        }
+        assert_eq!(
+            seed_to_soil.then(&soil_to_seed),
+            Some(PiecewiseShift::identity())
+        );
         assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            175622908
+            seed_to_soil.image(96, 100).into_iter().collect::<Vec<_>>(),
+            vec![inclusive(50, 51), inclusive(98, 100)]
         );
+        assert_eq!(PiecewiseShift::from_ranges([(0, 10, 5)]).inverse(), None);
+        assert_eq!(seed_to_soil.image(99, 96).into_iter().count(), 0);
     }
 
     #[test]
-    fn part2_test() {
+    fn shifts_at_the_ends_of_i64() {
+        // Sends the top five values up by 2, past `i64::MAX`.
+        let top = PiecewiseShift::from_ranges([(i64::MAX - 2, i64::MAX - 4, 5)]);
+        assert_eq!(top.apply(i64::MAX - 4), i64::MAX - 2);
+        assert_eq!(top.apply(i64::MAX), i64::MAX);
         assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            5200543
+            top.image(i64::MAX - 4, i64::MAX)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![inclusive(i64::MAX - 2, i64::MAX)]
+        );
+        assert_eq!(top.then(&top).unwrap().apply(i64::MAX - 4), i64::MAX);
+        assert_eq!(top.inverse(), None);
+
+        // Folds the negative half onto the positive one: a shift of 2^63, too big for an i64.
+        let fold = PiecewiseShift::from_ranges([(0, i64::MIN, i64::MAX)]);
+        assert_eq!(fold.apply(i64::MIN), 0);
+        assert_eq!(fold.then(&fold).unwrap().apply(i64::MIN), 0);
+        assert_eq!(fold.then(&top).unwrap().apply(-5), i64::MAX - 2);
+        assert_eq!(
+            fold.image(i64::MIN, i64::MAX - 1)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![inclusive(-1, i64::MAX - 1)]
         );
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    #[test]
+    fn composition_after_saturation() {
+        let top = PiecewiseShift::from_ranges([(i64::MAX - 2, i64::MAX - 4, 5)]);
+        // Pulls the top four values down by 7, so the two values `top` saturates at `i64::MAX`
+        // would both land on `i64::MAX - 7`.
+        let drop = PiecewiseShift::from_ranges([(i64::MAX - 10, i64::MAX - 3, 4)]);
+        assert_eq!(drop.apply(top.apply(i64::MAX - 1)), i64::MAX - 7);
+        assert_eq!(drop.apply(top.apply(i64::MAX)), i64::MAX - 7);
+        assert_eq!(top.then(&drop), None);
+        // Pushing saturated values further out, or leaving them be, keeps them saturated.
+        let rise = PiecewiseShift::from_ranges([(i64::MIN + 7, i64::MIN, 4)]);
+        for (first, second) in [(&top, &top), (&top, &rise), (&drop, &top), (&rise, &top)] {
+            let composed = first.then(second).unwrap();
+            for n in (0..12).flat_map(|k| [i64::MIN + k, i64::MAX - k]) {
+                assert_eq!(composed.apply(n), second.apply(first.apply(n)), "{}", n);
+            }
+        }
+        let error = part1(&format!(
+            "seeds: 1\n\na-to-b map:\n{} {} 5\n\nb-to-c map:\n{} {} 4\n",
+            i64::MAX - 2,
+            i64::MAX - 4,
+            i64::MAX - 10,
+            i64::MAX - 3
+        ))
+        .unwrap_err();
+        assert_eq!(
+            error.expected,
+            "a `b-to-c` map that keeps values saturated at the ends of i64 there"
+        );
     }
 
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    #[test]
+    fn empty_seed_ranges() {
+        let almanac = "seed-to-soil map:\n50 98 2\n52 50 48\n";
+        assert_eq!(part2(&format!("seeds: 79 0 55 13\n\n{}", almanac)), Ok(57));
+        let error = part2(&format!("seeds: 79 0\n\n{}", almanac)).unwrap_err();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (1, "at least one non-empty seed range")
+        );
     }
 }
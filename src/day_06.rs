@@ -1,54 +1,61 @@
-use advent_of_code::parse::{parsers, Parser};
+use advent_of_code::parse::parsers;
 
 use std::iter::zip;
 
-fn parse1(input: &str) -> (impl Iterator<Item = u64>, impl Iterator<Item = u64>) {
-    parsers::tag("Time:")
-        .ignore_and_then(
-            parsers::many_chars(|c| c == ' ')
-                .ignore_and_then(parsers::number())
-                .many(),
-        )
-        .skip_tag("\n")
-        .and_then(
-            parsers::tag("Distance:").ignore_and_then(
-                parsers::many_chars(|c| c == ' ')
-                    .ignore_and_then(parsers::number())
-                    .many(),
-            ),
-        )
-        .skip_tag("\n")
-        .parse(input)
-        .finish()
-        .expect("Failed to parse input")
+use crate::{
+    input::{InputError, InputLines},
+    solution::{Answer, Solution},
+};
+
+fn parse1(
+    input: &str,
+) -> Result<(impl Iterator<Item = u64>, impl Iterator<Item = u64>), InputError> {
+    let mut lines = InputLines::new(input);
+    let times = lines.next_line("a line like `Time:      7  15   30`", |line| {
+        line.tag("Time:")?
+            .words()
+            .map(|n| n.parse(parsers::number()))
+            .collect::<Result<Vec<u64>, usize>>()
+    })?;
+    let distances = lines.next_line("a line like `Distance:  9  40  200`", |line| {
+        line.tag("Distance:")?
+            .words()
+            .map(|n| n.parse(parsers::number()))
+            .collect::<Result<Vec<u64>, usize>>()
+    })?;
+    lines.finish()?;
+    Ok((times.into_iter(), distances.into_iter()))
 }
 
-fn parse2(input: &str) -> (u64, u64) {
-    parsers::tag("Time:")
-        .ignore_and_then(parsers::number_with_seps(" "))
-        .skip_tag("\n")
-        .and_then(parsers::tag("Distance:").ignore_and_then(parsers::number_with_seps(" ")))
-        .skip_tag("\n")
-        .parse(input)
-        .finish()
-        .expect("Failed to parse input")
+fn parse2(input: &str) -> Result<(u64, u64), InputError> {
+    let mut lines = InputLines::new(input);
+    let time = lines.next_line("a line like `Time:      7  15   30`", |line| {
+        line.tag("Time:")?
+            .trim_start()
+            .parse(parsers::number_with_seps(" "))
+    })?;
+    let distance = lines.next_line("a line like `Distance:  9  40  200`", |line| {
+        line.tag("Distance:")?
+            .trim_start()
+            .parse(parsers::number_with_seps(" "))
+    })?;
+    lines.finish()?;
+    Ok((time, distance))
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> u32 {
-    let (times, distances) = parse1(input);
-    zip(times, distances)
+pub fn part1(input: &str) -> Result<u32, InputError> {
+    let (times, distances) = parse1(input)?;
+    Ok(zip(times, distances)
         .map(|(time, distance)| {
             (1..time)
                 .filter(|hold| (time - hold) * hold > distance)
                 .count() as u32
         })
-        .product()
+        .product())
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> u64 {
-    let (time, distance) = parse2(input);
+pub fn part2(input: &str) -> Result<u64, InputError> {
+    let (time, distance) = parse2(input)?;
     let mut min = 1;
     let mut max = time / 2;
     while min + 1 < max {
@@ -58,82 +65,35 @@ pub fn part2(input: &str) -> u64 {
         } else if mid * (time - mid) > distance {
             max = mid;
         } else {
-            return time - 2 * mid - 1;
+            return Ok(time - 2 * mid - 1);
         }
     }
-    0
+    Ok(0)
 }
 
-#[allow(dead_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const EXAMPLE: &str = "Time:      7  15   30
-Distance:  9  40  200
-";
-    const DAY: Day = Day::Day06;
+pub struct Day06;
 
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 288);
+impl Solution for Day06 {
+    fn name(&self) -> &'static str {
+        "Wait For It"
     }
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(EXAMPLE), 71503);
+    fn day(&self) -> u32 {
+        6
     }
 
-    #[test]
-    fn part1_test() {
-        assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            1731600
-        );
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
     }
 
-    #[test]
-    fn part2_test() {
-        assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            40087680
-        );
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
-    }
-
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn parse(&self, part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(match part {
+            1 => parse1(input).map(drop),
+            _ => parse2(input).map(drop),
+        })
     }
 }
@@ -1,8 +1,10 @@
 use std::{cmp::Ordering, collections::BTreeMap, iter::zip};
 
-use advent_of_code::{
-    itertools::Itertools,
-    parse::{parsers, Parser},
+use advent_of_code::{itertools::Itertools, parse::parsers};
+
+use crate::{
+    input::{parse_lines, InputError},
+    solution::{Answer, Solution},
 };
 
 const CARDRANK: &'static str = "0123456789TJQKA";
@@ -107,111 +109,57 @@ impl PartialOrd for Hand {
     }
 }
 
-fn parse(input: &str) -> impl Iterator<Item = (String, u32)> {
-    parsers::many_chars(|c| c.is_alphanumeric())
-        .skip_tag(" ")
-        .and_then(parsers::number::<u32>())
-        .many_lines("\n")
-        .parse(input)
-        .finish()
-        .expect("Failed to parse input")
+fn parse(input: &str) -> Result<impl Iterator<Item = (String, u32)>, InputError> {
+    let lines = parse_lines(input, "a hand and bid like `32T3K 765`", |line| {
+        let (hand, bid) = line.split_once(" ")?;
+        Ok((
+            hand.parse(parsers::many_chars(|c| c.is_alphanumeric()))?,
+            bid.parse(parsers::number::<u32>())?,
+        ))
+    })?;
+    Ok(lines.into_iter())
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> u32 {
-    parse(input)
+pub fn part1(input: &str) -> Result<u32, InputError> {
+    Ok(parse(input)?
         .map(|(s, i)| (Hand::new(&s), i))
         .collect::<BTreeMap<Hand, u32>>()
         .into_iter()
         .enumerate()
         .map(|(idx, (_, value))| (idx as u32 + 1) * value)
-        .sum()
+        .sum())
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> u32 {
-    parse(input)
+pub fn part2(input: &str) -> Result<u32, InputError> {
+    Ok(parse(input)?
         .map(|(s, i)| (Hand::new_with_wild(&s, 'J'), i))
         .collect::<BTreeMap<Hand, u32>>()
         .into_iter()
         .enumerate()
         .map(|(idx, (_, value))| (idx as u32 + 1) * value)
-        .sum()
+        .sum())
 }
 
-#[allow(dead_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const EXAMPLE: &str = "32T3K 765
-T55J5 684
-KK677 28
-KTJJT 220
-QQQJA 483
-";
-    const DAY: Day = Day::Day07;
-
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 6440);
-    }
+pub struct Day07;
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(EXAMPLE), 5905);
+impl Solution for Day07 {
+    fn name(&self) -> &'static str {
+        "Camel Cards"
     }
 
-    #[test]
-    fn part1_test() {
-        assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            253603890
-        );
+    fn day(&self) -> u32 {
+        7
     }
 
-    #[test]
-    fn part2_test() {
-        assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            253630098
-        );
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
     }
 }
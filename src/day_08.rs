@@ -1,49 +1,83 @@
 use advent_of_code::{
-    itertools::Itertools,
-    number_theory::chinese_remainder::chinese_remainder_many_with_modulus,
-    parse::{parsers, Parser},
+    itertools::Itertools, number_theory::chinese_remainder::chinese_remainder_many_with_modulus,
+    parse::parsers,
 };
 use std::collections::HashMap;
 
-fn parse(input: &str) -> (String, HashMap<String, (String, String)>) {
-    parsers::many_chars(|c| c.is_alphabetic())
-        .skip_tag("\n\n")
-        .and_then(
-            parsers::many_chars(|c| c.is_alphanumeric())
-                .skip_tag(" = (")
-                .and_then(
-                    parsers::many_chars(|c| c.is_alphanumeric())
-                        .skip_tag(", ")
-                        .and_then(parsers::many_chars(|c| c.is_alphanumeric())),
-                )
-                .skip_tag(")")
-                .many_lines("\n")
-                .map(|iter| iter.collect::<HashMap<String, (String, String)>>()),
-        )
-        .parse(input)
-        .finish()
-        .expect("Failed to parse input")
+use crate::{
+    input::{Field, InputError, InputLines},
+    solution::{Answer, Solution},
+};
+
+type Network = HashMap<String, (String, String)>;
+
+fn parse(input: &str) -> Result<(String, Network), InputError> {
+    let mut lines = InputLines::new(input);
+    let instructions = lines.next_line("a line of `L` and `R` instructions", |line| {
+        if line.text().is_empty() {
+            return Err(line.column());
+        }
+        line.chars(|c| "LR".contains(c).then_some(c))
+            .map(String::from_iter)
+    })?;
+    lines.blank_line()?;
+    let label = |field: Field<'_>| field.parse(parsers::many_chars(|c| c.is_alphanumeric()));
+    let nodes = lines.until_blank("a node like `AAA = (BBB, CCC)`", |line| {
+        let (node, next) = line.split_once(" = (")?;
+        let (left, right) = next.split_once(", ")?;
+        let (right, rest) = right.split_once(")")?;
+        rest.empty()?;
+        Ok((
+            label(node)?,
+            (label(left)?, left.column()),
+            (label(right)?, right.column()),
+        ))
+    })?;
+    lines.finish()?;
+    let network: Network = nodes
+        .iter()
+        .map(|(node, (left, _), (right, _))| (node.clone(), (left.clone(), right.clone())))
+        .collect();
+    // The nodes start on line 3, after the instructions and a blank line.
+    for (idx, (_, left, right)) in nodes.iter().enumerate() {
+        for (label, column) in [left, right] {
+            if !network.contains_key(label) {
+                return Err(InputError::at(
+                    input,
+                    idx + 3,
+                    *column,
+                    "a node defined in the network",
+                ));
+            }
+        }
+    }
+    Ok((instructions, network))
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> u32 {
-    let (instructions, map) = parse(input);
+pub fn part1(input: &str) -> Result<u32, InputError> {
+    let (instructions, map) = parse(input)?;
+    if !map.contains_key("AAA") {
+        return Err(InputError::end_of_input(input, "a node named `AAA`"));
+    }
     let mut current = "AAA";
     let mut steps = 0;
     for instruction in instructions.chars().cycle() {
         if current == "ZZZ" {
             break;
         }
-        let (l, r) = map.get(current).unwrap();
+        // `parse` checked that every node leads to a defined one.
+        let (l, r) = &map[current];
         current = if instruction == 'R' { r } else { l };
         steps += 1;
     }
-    steps
+    Ok(steps)
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> i64 {
-    let (instructions, map) = parse(input);
+pub fn part2(input: &str) -> Result<i64, InputError> {
+    let (instructions, map) = parse(input)?;
+    if !map.keys().any(|s| s.ends_with('A')) {
+        return Err(InputError::end_of_input(input, "a node ending in `A`"));
+    }
     let cycles = map
         .keys()
         .filter(|s| s.chars().last() == Some('A'))
@@ -51,7 +85,8 @@ pub fn part2(input: &str) -> i64 {
             let path = instructions.chars().enumerate().cycle().scan(
                 start.clone(),
                 |current, (instruction_idx, instruction)| {
-                    let (l, r) = map.get(current).unwrap();
+                    // `parse` checked that every node leads to a defined one.
+                    let (l, r) = &map[current];
                     *current = if instruction == 'R' {
                         r.clone()
                     } else {
@@ -111,134 +146,82 @@ pub fn part2(input: &str) -> i64 {
             remainder
         })
         .min()
-        .unwrap()
+        .ok_or_else(|| {
+            InputError::end_of_input(
+                input,
+                "a network where every `..A` node reaches a `..Z` node on the same step",
+            )
+        })
 }
 
-#[allow(dead_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
+pub struct Day08;
 
-    const DAY: Day = Day::Day08;
+impl Solution for Day08 {
+    fn name(&self) -> &'static str {
+        "Haunted Wasteland"
+    }
 
-    #[test]
-    fn part1_example() {
-        assert_eq!(
-            part1(
-                "RL
-
-AAA = (BBB, CCC)
-BBB = (DDD, EEE)
-CCC = (ZZZ, GGG)
-DDD = (DDD, DDD)
-EEE = (EEE, EEE)
-GGG = (GGG, GGG)
-ZZZ = (ZZZ, ZZZ)
-"
-            ),
-            2
-        );
-        assert_eq!(
-            part1(
-                "LLR
+    fn day(&self) -> u32 {
+        8
+    }
 
-AAA = (BBB, BBB)
-BBB = (AAA, ZZZ)
-ZZZ = (ZZZ, ZZZ)
-"
-            ),
-            6
-        );
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
     }
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(
-            part2(
-                "LR
-
-11A = (11B, XXX)
-11B = (XXX, 11Z)
-11Z = (11B, XXX)
-22A = (22B, XXX)
-22B = (22C, 22C)
-22C = (22Z, 22Z)
-22Z = (22B, 22B)
-XXX = (XXX, XXX)
-"
-            ),
-            6
-        );
-        assert_eq!(
-            part2(
-                "LR
-
-11A = (11D, XXX)
-11D = (XXX, 11E)
-11E = (11B, XXX)
-11B = (XXX, 11Z)
-11Z = (11B, XXX)
-22A = (22B, XXX)
-22B = (22C, 22C)
-22C = (22Z, 22Z)
-22Z = (22B, 22B)
-XXX = (XXX, XXX)
-"
-            ),
-            6
-        );
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
     }
 
-    #[test]
-    fn part1_test() {
-        assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            11309
-        );
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
     }
+}
+
+#[allow(dead_code)]
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
-    fn part2_test() {
+    fn undefined_node() {
+        let error = part1(
+            "LR
+
+AAA = (BBB, ZZZ)
+BBB = (AAA, ZZY)
+ZZZ = (ZZZ, ZZZ)
+",
+        )
+        .unwrap_err();
         assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            13740108158591
+            (error.line, error.column, error.snippet.as_str()),
+            (4, 13, "ZZY)")
         );
+        let error = part1("LRX\n\nAAA = (AAA, AAA)\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 3));
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
-    }
+    #[test]
+    fn no_common_step() {
+        // No start node at all.
+        let error = part2("L\n\nBBB = (BBB, BBB)\n").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+        // `11A` never reaches a `..Z` node.
+        let error = part2("L\n\n11A = (11B, 11B)\n11B = (11A, 11A)\n").unwrap_err();
+        assert_eq!((error.line, error.column), (5, 1));
+        // `11Z` is only reached on odd steps and `22Z` only on even ones.
+        let error = part2(
+            "L
 
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+11A = (11Z, 11Z)
+11Z = (11A, 11A)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22B, 22B)
+",
+        )
+        .unwrap_err();
+        assert_eq!((error.line, error.column), (8, 1));
     }
 }
@@ -1,20 +1,21 @@
-use advent_of_code::{
-    difference_sequence::DifferenceSequence,
-    parse::{parsers, Parser},
+use advent_of_code::{difference_sequence::DifferenceSequence, parse::parsers};
+
+use crate::{
+    input::{parse_lines, InputError},
+    solution::{Answer, Solution},
 };
 
-fn parse(input: &str) -> impl Iterator<Item = impl Iterator<Item = i64>> {
-    parsers::signed_number::<i64>()
-        .list(" ")
-        .many_lines("\n")
-        .parse(input)
-        .finish()
-        .expect("Failed to parse input")
+fn parse(input: &str) -> Result<impl Iterator<Item = impl Iterator<Item = i64>>, InputError> {
+    let lines = parse_lines(input, "a line of space-separated integers", |line| {
+        line.split(" ")
+            .map(|n| n.parse(parsers::signed_number::<i64>()))
+            .collect::<Result<Vec<i64>, usize>>()
+    })?;
+    Ok(lines.into_iter().map(Vec::into_iter))
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> i64 {
-    parse(input)
+pub fn part1(input: &str) -> Result<i64, InputError> {
+    Ok(parse(input)?
         .map(|iter| {
             let initial_list: Vec<i64> = iter.collect();
             let initial_length = initial_list.len();
@@ -22,87 +23,35 @@ pub fn part1(input: &str) -> i64 {
                 .nth(initial_length)
                 .unwrap()
         })
-        .sum()
+        .sum())
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> i64 {
-    parse(input)
+pub fn part2(input: &str) -> Result<i64, InputError> {
+    Ok(parse(input)?
         .map(|iter| DifferenceSequence::from_iter(iter).step_back())
-        .sum()
+        .sum())
 }
 
-#[allow(dead_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const EXAMPLE: &str = "0 3 6 9 12 15
-1 3 6 10 15 21
-10 13 16 21 30 45
-";
-    const DAY: Day = Day::Day09;
-
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 114);
-    }
+pub struct Day09;
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(EXAMPLE), 2);
+impl Solution for Day09 {
+    fn name(&self) -> &'static str {
+        "Mirage Maintenance"
     }
 
-    #[test]
-    fn part1_test() {
-        assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            2175229206
-        );
+    fn day(&self) -> u32 {
+        9
     }
 
-    #[test]
-    fn part2_test() {
-        assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            942
-        );
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
     }
 }
@@ -1,12 +1,21 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::{
+    collections::HashSet,
+    error::Error,
+    fmt::{self, Display},
+};
 
 use advent_of_code::{
     grid::{Grid, GridPoint, GridPointDelta, EAST, NORTH, PLUS_ADJACENT, SOUTH, WEST},
     parse::{parsers, Parser},
 };
 
+use crate::{
+    input::InputError,
+    solution::{Answer, Solution},
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Cell {
+pub enum Cell {
     Empty,
     Horizontal,
     Vertical,
@@ -45,6 +54,23 @@ impl Cell {
         }
     }
 
+    /// The pipe connecting `a` and `b`, in either order.
+    fn with_directions(a: GridPointDelta<isize>, b: GridPointDelta<isize>) -> Option<Self> {
+        [
+            Self::Horizontal,
+            Self::Vertical,
+            Self::NorthEast,
+            Self::NorthWest,
+            Self::SouthEast,
+            Self::SouthWest,
+        ]
+        .into_iter()
+        .find(|pipe| {
+            let directions = pipe.valid_directions();
+            a != b && directions.contains(&a) && directions.contains(&b)
+        })
+    }
+
     fn valid_directions(self) -> Vec<GridPointDelta<isize>> {
         match self {
             Self::Empty => vec![],
@@ -59,97 +85,191 @@ impl Cell {
     }
 }
 
-fn parse(input: &str) -> Vec<Vec<Cell>> {
-    parsers::many_chars(|c| c != '\n')
-        .map(|c| c.chars().filter_map(Cell::of_char).collect::<Vec<Cell>>())
+const EXPECTED: &str = "one of `.`, `-`, `|`, `L`, `J`, `7`, `F`, `S`";
+
+fn is_tile(c: char) -> bool {
+    Cell::of_char(c).is_some()
+}
+
+fn parse(input: &str) -> Result<Grid<Cell>, InputError> {
+    let rows: Vec<Vec<Cell>> = parsers::chars(is_tile)
+        .map(|c| Cell::of_char(c).unwrap())
+        .many()
+        .map(|i| i.collect())
         .many_lines("\n")
+        .map(|i| i.collect())
         .parse(input)
         .finish()
-        .expect("Failed to parse input")
-        .collect()
+        .map_err(|_| InputError::in_grid(input, EXPECTED, is_tile))?;
+    Grid::of_vec_of_vecs(rows).map_err(|_| InputError::in_grid(input, EXPECTED, is_tile))
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> usize {
-    let grid: Grid<Cell> = Grid::of_vec_of_vecs(parse(input)).unwrap();
-    let start = grid.find(&Cell::Start).unwrap();
-    let mut seen: HashMap<GridPoint<usize>, usize> = HashMap::new();
-    seen.insert(start, 0);
-    let mut to_search: VecDeque<(usize, GridPoint<usize>)> = VecDeque::new();
-
-    for delta in PLUS_ADJACENT.iter() {
-        if let Some(next_loc) = start + *delta {
-            let next = grid.get(next_loc).unwrap();
-            if next.valid_connection(*delta) {
-                seen.insert(next_loc, 1);
-                to_search.push_back((1, next_loc));
-            }
+/// Why a pipe map has no single loop through `S`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipeError {
+    MissingStart,
+    /// A second `S`.
+    MultipleStarts(GridPoint<usize>),
+    /// `S` has more than two neighbours connecting to it, so its pipe is ambiguous.
+    AmbiguousStart {
+        start: GridPoint<usize>,
+        connections: usize,
+    },
+    /// A pipe on the loop leads off the map.
+    OffGrid {
+        at: GridPoint<usize>,
+        dir: GridPointDelta<isize>,
+    },
+    /// A pipe on the loop, or `S`, leads to a tile that does not connect back.
+    Disconnected(GridPoint<usize>),
+}
+
+impl PipeError {
+    /// The tile the problem was found at.
+    pub fn point(&self) -> Option<GridPoint<usize>> {
+        match *self {
+            Self::MissingStart => None,
+            Self::MultipleStarts(point)
+            | Self::AmbiguousStart { start: point, .. }
+            | Self::OffGrid { at: point, .. }
+            | Self::Disconnected(point) => Some(point),
         }
     }
+}
 
-    while let Some((distance, current_loc)) = to_search.pop_front() {
-        let current = grid.get(current_loc).unwrap();
-        for delta in current.valid_directions() {
-            let next_loc = (current_loc + delta).unwrap();
-            if !seen.contains_key(&next_loc) {
-                seen.insert(next_loc, distance + 1);
-                to_search.push_back((distance + 1, next_loc));
+impl Display for PipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingStart => write!(f, "there is no start tile `S`"),
+            Self::MultipleStarts(_) => write!(f, "this is a second start tile"),
+            Self::AmbiguousStart { connections, .. } => write!(
+                f,
+                "the start tile connects to {} pipes, so its shape is ambiguous",
+                connections
+            ),
+            Self::OffGrid { dir, .. } => write!(
+                f,
+                "this pipe leads off the map heading {}",
+                match *dir {
+                    NORTH => "north",
+                    SOUTH => "south",
+                    EAST => "east",
+                    _ => "west",
+                }
+            ),
+            Self::Disconnected(_) => {
+                write!(f, "this pipe leads to a tile that does not connect back")
             }
         }
     }
+}
+
+impl Error for PipeError {}
 
-    *(seen.values().max().unwrap())
+/// The loop running through `S`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopAnalysis {
+    /// The pipe hidden under `S`.
+    pub start_pipe: Cell,
+    /// The tiles of the loop in order, starting at `S`.
+    pub path: Vec<GridPoint<usize>>,
+    /// The tiles enclosed by the loop, row by row.
+    pub enclosed: Vec<GridPoint<usize>>,
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> u32 {
-    let mut grid: Grid<Cell> = Grid::of_vec_of_vecs(parse(input)).unwrap();
-    let start = grid.find(&Cell::Start).unwrap();
-    let mut seen: HashSet<GridPoint<usize>> = HashSet::new();
-    seen.insert(start);
-    let mut to_search: VecDeque<GridPoint<usize>> = VecDeque::new();
-
-    for delta in PLUS_ADJACENT.iter() {
-        if let Some(next_loc) = start + *delta {
-            let next = grid.get(next_loc).unwrap();
-            if next.valid_connection(*delta) {
-                seen.insert(next_loc);
-                to_search.push_back(next_loc);
-            }
-        }
+impl LoopAnalysis {
+    /// The area inside the line through the centres of the loop's tiles, by the shoelace formula.
+    pub fn area(&self) -> usize {
+        let twice_area: isize = self
+            .path
+            .iter()
+            .zip(self.path.iter().cycle().skip(1))
+            .map(|(a, b)| (a.row() * b.col()) as isize - (b.row() * a.col()) as isize)
+            .sum();
+        twice_area.unsigned_abs() / 2
     }
 
-    while let Some(current_loc) = to_search.pop_front() {
-        let current = grid.get(current_loc).unwrap();
-        for delta in current.valid_directions() {
-            let next_loc = (current_loc + delta).unwrap();
-            if !seen.contains(&next_loc) {
-                seen.insert(next_loc);
-                to_search.push_back(next_loc);
-            }
-        }
+    /// The number of enclosed tiles by Pick's theorem, `A = i + b/2 - 1`, with the loop's tiles as
+    /// the boundary points. Agrees with `enclosed.len()`.
+    pub fn enclosed_by_pick(&self) -> usize {
+        self.area() + 1 - self.path.len() / 2
     }
+}
 
-    let dimensions = grid.dimensions();
+/// Follows the loop from `S`, after working out which pipe `S` must be from the neighbours that
+/// connect to it.
+pub fn analyze_grid(grid: &Grid<Cell>) -> Result<LoopAnalysis, PipeError> {
+    let mut starts = grid
+        .iter_points()
+        .filter(|&point| grid.get(point).unwrap() == &Cell::Start);
+    let start = starts.next().ok_or(PipeError::MissingStart)?;
+    if let Some(other) = starts.next() {
+        return Err(PipeError::MultipleStarts(other));
+    }
+    let grid_dimensions = grid.dimensions();
+    let connects = |from: GridPoint<usize>, delta: GridPointDelta<isize>| {
+        from.add_checked(delta, &grid_dimensions)
+            .is_some_and(|next| grid.get(next).unwrap().valid_connection(delta))
+    };
+    let start_directions: Vec<GridPointDelta<isize>> = PLUS_ADJACENT
+        .iter()
+        .copied()
+        .filter(|&delta| connects(start, delta))
+        .collect();
+    let start_pipe = match start_directions[..] {
+        [a, b] => Cell::with_directions(a, b).unwrap(),
+        [_, _, ..] => {
+            return Err(PipeError::AmbiguousStart {
+                start,
+                connections: start_directions.len(),
+            })
+        }
+        _ => return Err(PipeError::Disconnected(start)),
+    };
+    let pipe_at = |point: GridPoint<usize>| {
+        if point == start {
+            start_pipe
+        } else {
+            *grid.get(point).unwrap()
+        }
+    };
 
-    for point in dimensions.all_contained_points() {
-        if !seen.contains(&point) {
-            grid.set(point, Cell::Empty).unwrap();
+    let mut path = vec![start];
+    let mut dir = start_directions[0];
+    let mut current = start;
+    loop {
+        current = (current + dir).unwrap();
+        if current == start {
+            break;
+        }
+        path.push(current);
+        dir = pipe_at(current)
+            .valid_directions()
+            .into_iter()
+            .find(|&next| next != -dir)
+            .unwrap();
+        if current.add_checked(dir, &grid_dimensions).is_none() {
+            return Err(PipeError::OffGrid { at: current, dir });
+        }
+        if !connects(current, dir) {
+            return Err(PipeError::Disconnected(current));
         }
     }
 
-    let mut inside_count = 0;
-    for row in GridPoint::new(0, 0).traverse_by(SOUTH, dimensions) {
+    let on_loop: HashSet<GridPoint<usize>> = path.iter().copied().collect();
+    let mut enclosed = vec![];
+    for row in GridPoint::new(0, 0).traverse_by(SOUTH, grid_dimensions) {
         let mut inside = false;
         let mut last_l: Option<Cell> = None;
-        for loc in row.traverse_by(EAST, dimensions) {
-            match *(grid.get(loc).unwrap()) {
-                Cell::Vertical => inside = !inside,
-                Cell::Empty => {
-                    if inside {
-                        inside_count += 1
-                    }
+        for loc in row.traverse_by(EAST, grid_dimensions) {
+            if !on_loop.contains(&loc) {
+                if inside {
+                    enclosed.push(loc);
                 }
+                continue;
+            }
+            match pipe_at(loc) {
+                Cell::Vertical => inside = !inside,
                 Cell::NorthEast => last_l = Some(Cell::NorthEast),
                 Cell::SouthEast => last_l = Some(Cell::SouthEast),
                 Cell::SouthWest => {
@@ -169,143 +289,149 @@ pub fn part2(input: &str) -> u32 {
         }
     }
 
-    inside_count
+    Ok(LoopAnalysis {
+        start_pipe,
+        path,
+        enclosed,
+    })
+}
+
+/// Parses the map and analyzes its loop, placing any [`PipeError`] at the tile it was found at.
+pub fn analyze(input: &str) -> Result<LoopAnalysis, InputError> {
+    analyze_grid(&parse(input)?).map_err(|err| {
+        let expected = format!("a pipe map with one loop through `S` ({})", err);
+        match err.point() {
+            Some(point) => InputError::at(input, point.row() + 1, point.col() + 1, &expected),
+            None => InputError::end_of_input(input, &expected),
+        }
+    })
+}
+
+pub fn part1(input: &str) -> Result<usize, InputError> {
+    Ok(analyze(input)?.path.len() / 2)
+}
+
+pub fn part2(input: &str) -> Result<usize, InputError> {
+    let analysis = analyze(input)?;
+    debug_assert_eq!(analysis.enclosed.len(), analysis.enclosed_by_pick());
+    Ok(analysis.enclosed.len())
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    fn name(&self) -> &'static str {
+        "Pipe Maze"
+    }
+
+    fn day(&self) -> u32 {
+        10
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
+    }
+
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
+    }
 }
 
 #[allow(dead_code)]
 #[cfg(test)]
 mod tests {
     use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
+    use crate::examples::load_example;
 
-    const DAY: Day = Day::Day10;
+    #[test]
+    fn infers_start_pipe() {
+        let analysis = analyze(&load_example(10, "part1_a").unwrap()).unwrap();
+        assert_eq!(analysis.start_pipe, Cell::SouthEast);
+        assert_eq!(analysis.path.len(), 8);
+        assert_eq!(analysis.path[0], GridPoint::new(1, 1));
+        assert_eq!(analysis.enclosed, vec![GridPoint::new(2, 2)]);
+        let analysis = analyze(&load_example(10, "part2_c").unwrap()).unwrap();
+        assert_eq!(analysis.start_pipe, Cell::SouthWest);
+    }
 
     #[test]
-    fn part1_example() {
-        assert_eq!(
-            part1(
-                ".....
-.S-7.
-.|.|.
-.L-J.
-.....
-"
-            ),
-            4
-        );
-        assert_eq!(
-            part1(
-                "..F7.
-.FJ|.
-SJ.L7
-|F--J
-LJ...
-"
-            ),
-            8
-        );
+    fn loop_is_connected_in_order() {
+        for name in ["part1_a", "part1_b", "part2_a", "part2_b", "part2_c"] {
+            let analysis = analyze(&load_example(10, name).unwrap()).unwrap();
+            for (a, b) in analysis
+                .path
+                .iter()
+                .zip(analysis.path.iter().cycle().skip(1))
+            {
+                assert_eq!(b.sub::<isize>(*a).unwrap().l1_norm(), 1, "{}", name);
+            }
+            assert_eq!(
+                analysis.enclosed.len(),
+                analysis.enclosed_by_pick(),
+                "{}",
+                name
+            );
+        }
     }
 
     #[test]
-    fn part2_example() {
+    fn rejects_broken_maps() {
+        let error = |input: &str| analyze_grid(&parse(input).unwrap()).unwrap_err();
+        assert_eq!(error("...\n.-.\n"), PipeError::MissingStart);
         assert_eq!(
-            part2(
-                "...........
-.S-------7.
-.|F-----7|.
-.||.....||.
-.||.....||.
-.|L-7.F-J|.
-.|..|.|..|.
-.L--J.L--J.
-...........
-"
-            ),
-            4
+            error("S-7\n|.|\nL-S\n"),
+            PipeError::MultipleStarts(GridPoint::new(2, 2))
         );
         assert_eq!(
-            part2(
-                ".F----7F7F7F7F-7....
-.|F--7||||||||FJ....
-.||.FJ||||||||L7....
-FJL7L7LJLJ||LJ.L-7..
-L--J.L7...LJS7F-7L7.
-....F-J..F7FJ|L7L7L7
-....L7.F7||L7|.L7L7|
-.....|FJLJ|FJ|F7|.LJ
-....FJL-7.||.||||...
-....L---J.LJ.LJLJ...
-"
-            ),
-            8
+            error(".|.\n-S-\n.|.\n"),
+            PipeError::AmbiguousStart {
+                start: GridPoint::new(1, 1),
+                connections: 4
+            }
         );
+        // The walk sets off along the start's first connection in `PLUS_ADJACENT` order, east here,
+        // so a broken loop fails at the far side of the break.
         assert_eq!(
-            part2(
-                "FF7FSF7F7F7F7F7F---7
-L|LJ||||||||||||F--J
-FL-7LJLJ||||||LJL-77
-F--JF--7||LJLJ7F7FJ-
-L---JF-JLJ.||-FJLJJ7
-|F|F-JF---7F7-L7L|7|
-|FFJF7L7F-JF7|JL---7
-7-L-JL7||F7|L7F-7F7|
-L.L7LFJ|||||FJL7||LJ
-L7JLJL-JLJLJL--JLJ.L
-"
-            ),
-            10
+            error("S-7\n|.|\n|.L\n"),
+            PipeError::OffGrid {
+                at: GridPoint::new(2, 2),
+                dir: EAST,
+            }
         );
-    }
-
-    #[test]
-    fn part1_test() {
         assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            6754
+            error("S-7\n|.|\nL.J\n"),
+            PipeError::Disconnected(GridPoint::new(2, 2))
         );
-    }
-
-    #[test]
-    fn part2_test() {
         assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            0
+            error("S..\n...\n"),
+            PipeError::Disconnected(GridPoint::new(0, 0))
         );
-    }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+        let error = part1("S-7\n|x|\nL-J\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        let error = part2("S-7\n|.|\nL.J\n").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 3));
+        assert!(error.expected.contains("does not connect back"));
+        let error = part1("S-7\n|.|\nL-J\n.\n").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 2));
     }
 
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    #[test]
+    fn enclosed_tiles() {
+        let analysis = analyze(&load_example(10, "part2_a").unwrap()).unwrap();
+        assert_eq!(
+            analysis.enclosed,
+            vec![
+                GridPoint::new(6, 2),
+                GridPoint::new(6, 3),
+                GridPoint::new(6, 7),
+                GridPoint::new(6, 8),
+            ]
+        );
+        assert_eq!(analysis.area(), 4 + analysis.path.len() / 2 - 1);
     }
 }
@@ -3,14 +3,21 @@ use advent_of_code::{
     parse::{parsers, Parser},
 };
 
+use crate::{
+    input::InputError,
+    solution::{Answer, Solution},
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Cell {
     Empty,
     Galaxy,
 }
 
-fn parse(input: &str) -> Vec<Vec<Cell>> {
-    parsers::tag_replace(".", Cell::Empty)
+const EXPECTED: &str = "`.` or `#`";
+
+fn parse(input: &str) -> Result<Grid<Cell>, InputError> {
+    let rows = parsers::tag_replace(".", Cell::Empty)
         .or(parsers::tag_replace("#", Cell::Galaxy))
         .many()
         .map(|many| many.collect::<Vec<Cell>>())
@@ -18,11 +25,12 @@ fn parse(input: &str) -> Vec<Vec<Cell>> {
         .map(|lines| lines.collect())
         .parse(input)
         .finish()
-        .expect("Failed to parse input")
+        .map_err(|_| InputError::invalid_char(input, EXPECTED, |c| c == '.' || c == '#'))?;
+    Grid::of_vec_of_vecs(rows).map_err(|_| InputError::in_grid(input, EXPECTED, |_| true))
 }
 
-pub fn galaxy_dist(input: &str, expansion: u64) -> u64 {
-    let map = Grid::of_vec_of_vecs(parse(input)).unwrap();
+pub fn galaxy_dist(input: &str, expansion: u64) -> Result<u64, InputError> {
+    let map = parse(input)?;
     let galaxies: Vec<GridPoint<usize>> = map
         .iter_points()
         .filter(|point| map.get(*point) == Ok(&Cell::Galaxy))
@@ -66,98 +74,51 @@ pub fn galaxy_dist(input: &str, expansion: u64) -> u64 {
             total_distance += expansion * (left_count * right_count) as u64;
         }
     }
-    total_distance
+    Ok(total_distance)
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> u64 {
+pub fn part1(input: &str) -> Result<u64, InputError> {
     galaxy_dist(input, 1)
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> u64 {
+pub fn part2(input: &str) -> Result<u64, InputError> {
     galaxy_dist(input, 999999)
 }
 
-#[allow(dead_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const EXAMPLE: &str = "...#......
-.......#..
-#.........
-..........
-......#...
-.#........
-.........#
-..........
-.......#..
-#...#.....
-";
-    const DAY: Day = Day::Day11;
+pub struct Day11;
 
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 374);
+impl Solution for Day11 {
+    fn name(&self) -> &'static str {
+        "Cosmic Expansion"
     }
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(galaxy_dist(EXAMPLE, 9), 1030);
-        assert_eq!(galaxy_dist(EXAMPLE, 99), 8410);
+    fn day(&self) -> u32 {
+        11
     }
 
-    #[test]
-    fn part1_test() {
-        assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            10885634
-        );
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
     }
 
-    #[test]
-    fn part2_test() {
-        assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            707505470642
-        );
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
     }
+}
 
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+#[allow(dead_code)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../examples/11/example.in");
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(galaxy_dist(EXAMPLE, 9), Ok(1030));
+        assert_eq!(galaxy_dist(EXAMPLE, 99), Ok(8410));
     }
 }
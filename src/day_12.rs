@@ -1,330 +1,560 @@
-use advent_of_code::parse::{parsers, Parser};
-use std::{collections::HashMap, iter::zip, mem::take};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Spring {
+use advent_of_code::parse::parsers;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    input::{parse_lines, InputError},
+    solution::{Answer, Solution},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Spring {
     Working,
     Broken,
     Unknown,
 }
 
 impl Spring {
-    fn matches(self, other: Self) -> bool {
-        match (self, other) {
-            (Self::Broken, Self::Broken)
-            | (Self::Working, Self::Working)
-            | (Self::Unknown, _)
-            | (_, Self::Unknown) => true,
-            _ => false,
+    fn to_char(self) -> char {
+        match self {
+            Self::Working => '.',
+            Self::Broken => '#',
+            Self::Unknown => '?',
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Island(Vec<Spring>);
+fn parse(input: &str) -> Result<impl Iterator<Item = Record>, InputError> {
+    let lines = parse_lines(input, "a record like `???.### 1,1,3`", |line| {
+        let (springs, groups) = line.split_once(" ")?;
+        let springs = springs.chars(|c| match c {
+            '.' => Some(Spring::Working),
+            '#' => Some(Spring::Broken),
+            '?' => Some(Spring::Unknown),
+            _ => None,
+        })?;
+        let groups = groups
+            .split(",")
+            .map(|n| n.parse(parsers::number()))
+            .collect::<Result<Vec<usize>, usize>>()?;
+        Ok(Record::new(springs, groups))
+    })?;
+    Ok(lines.into_iter())
+}
+
+/// An integer type arrangements can be counted in.
+pub trait Count: Clone {
+    fn zero() -> Self;
+    fn one() -> Self;
+    /// The sum, or `None` if it does not fit in the type.
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_count {
+    ($($t:ty),*) => {
+        $(impl Count for $t {
+            fn zero() -> Self {
+                0
+            }
+
+            fn one() -> Self {
+                1
+            }
+
+            fn checked_add(&self, other: &Self) -> Option<Self> {
+                <$t>::checked_add(*self, *other)
+            }
+        })*
+    };
+}
+
+impl_count!(u64, u128);
 
-impl Island {
-    fn empty() -> Self {
-        Island(vec![])
+/// Whether there is any arrangement at all, which never overflows.
+impl Count for bool {
+    fn zero() -> Self {
+        false
     }
 
-    fn matches(&self, other: &Self) -> bool {
-        zip(self.0.iter(), other.0.iter()).all(|(a, b)| a.matches(*b))
+    fn one() -> Self {
+        true
     }
 
-    fn all_working(len: usize) -> Self {
-        Island(vec![Spring::Working; len as usize])
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(*self || *other)
     }
+}
 
-    fn append_spring_type(&mut self, spring: Spring, len: usize) {
-        self.0.extend(vec![spring; len]);
+#[cfg(feature = "bigint")]
+impl Count for num_bigint::BigUint {
+    fn zero() -> Self {
+        Self::from(0u8)
     }
 
-    fn len(&self) -> usize {
-        self.0.len()
+    fn one() -> Self {
+        Self::from(1u8)
     }
 
-    fn tail(&self, to_skip: usize) -> Self {
-        Island(self.0.iter().skip(to_skip).copied().collect())
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
     }
+}
 
-    fn count_spring_arrangements(&self, validation: Vec<usize>) -> u64 {
-        if validation.is_empty() {
-            let option = Island::all_working(self.len());
-            if option.matches(self) {
-                return 1;
-            } else {
-                return 0;
+/// There are more arrangements than the chosen [`Count`] type can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "too many arrangements to count")
+    }
+}
+
+impl Error for Overflow {}
+
+/// Renders springs the way the puzzle writes them.
+#[allow(dead_code)]
+pub fn render(springs: &[Spring]) -> String {
+    springs.iter().map(|spring| spring.to_char()).collect()
+}
+
+/// A row of springs with the sizes of its groups of broken springs, plus prefix counts of springs
+/// known to be working or broken so that any run can be checked in constant time.
+#[derive(Debug, Clone)]
+pub struct Record {
+    springs: Vec<Spring>,
+    groups: Vec<usize>,
+    /// `working[i]` is the number of springs before `i` known to be working.
+    working: Vec<usize>,
+    /// `broken[i]` is the number of springs before `i` known to be broken.
+    broken: Vec<usize>,
+}
+
+impl Record {
+    pub fn new(springs: Vec<Spring>, groups: Vec<usize>) -> Self {
+        let prefix = |kind: Spring| {
+            let mut counts = Vec::with_capacity(springs.len() + 1);
+            counts.push(0);
+            for &spring in &springs {
+                counts.push(counts.last().unwrap() + usize::from(spring == kind));
             }
+            counts
+        };
+        let (working, broken) = (prefix(Spring::Working), prefix(Spring::Broken));
+        Record {
+            springs,
+            groups,
+            working,
+            broken,
         }
-        let mut spring_arrangements_cache: HashMap<(usize, usize), u64> = HashMap::new();
-        for validation_idx in (0..validation.len()).rev() {
-            for spring_idx in (0..self.0.len()).rev() {
-                let key = (validation_idx, spring_idx);
-                let next_key = (validation_idx, spring_idx + 1);
-                let if_skip = if self.0[spring_idx].matches(Spring::Working) {
-                    spring_arrangements_cache
-                        .get(&next_key)
-                        .copied()
-                        .unwrap_or(0)
-                } else {
-                    0
-                };
-                let group_len = validation[validation_idx];
-                let tail = self.tail(spring_idx);
-
-                if validation_idx == validation.len() - 1 {
-                    let to_insert = if tail.len() < group_len {
-                        0
-                    } else {
-                        let mut desired = Island::empty();
-                        desired.append_spring_type(Spring::Broken, group_len);
-                        desired.append_spring_type(Spring::Working, tail.len() - group_len);
-                        if tail.matches(&desired) {
-                            1
-                        } else {
-                            0
-                        }
-                    } + if_skip;
-                    spring_arrangements_cache.insert(key, to_insert);
+    }
+
+    /// The record repeated `factor` times, with `joiner` between the copies of the springs.
+    pub fn unfold(&self, factor: usize, joiner: Spring) -> Self {
+        let mut springs = Vec::with_capacity((self.springs.len() + 1) * factor);
+        for copy in 0..factor {
+            if copy > 0 {
+                springs.push(joiner);
+            }
+            springs.extend(&self.springs);
+        }
+        Record::new(springs, self.groups.repeat(factor))
+    }
+
+    fn len(&self) -> usize {
+        self.springs.len()
+    }
+
+    /// Whether every spring in `start..end` could be working.
+    fn can_work(&self, start: usize, end: usize) -> bool {
+        self.broken[end] == self.broken[start]
+    }
+
+    /// Where the arrangement continues if group `j` is broken from spring `i`, including the working
+    /// spring after the group, or `None` if the group does not fit there.
+    fn group_end(&self, i: usize, j: usize) -> Option<usize> {
+        let end = i + self.groups.get(j)?;
+        let fits = end <= self.len()
+            && self.working[end] == self.working[i]
+            && (end == self.len() || self.can_work(end, end + 1));
+        fits.then_some((end + 1).min(self.len()))
+    }
+
+    /// The arrangements of `springs[i..]` matching `groups[j..]`, given the same counts for group
+    /// `j + 1` onwards in `next`.
+    fn ways<C: Count>(&self, i: usize, j: usize, row: &[C], next: &[C]) -> Result<C, Overflow> {
+        let mut count = C::zero();
+        if i < self.len() && self.can_work(i, i + 1) {
+            count = row[i + 1].clone();
+        }
+        if let Some(end) = self.group_end(i, j) {
+            count = count.checked_add(&next[end]).ok_or(Overflow)?;
+        }
+        Ok(count)
+    }
+
+    /// The arrangements with no groups left: 1 wherever the rest of the row could be working.
+    fn last_row<C: Count>(&self) -> Vec<C> {
+        (0..=self.len())
+            .map(|i| {
+                if self.can_work(i, self.len()) {
+                    C::one()
                 } else {
-                    let to_insert = if tail.len() < group_len + 2 {
-                        0
-                    } else {
-                        let mut desired = Island::empty();
-                        desired.append_spring_type(Spring::Broken, group_len);
-                        desired.append_spring_type(Spring::Working, 1);
-
-                        let after = spring_arrangements_cache
-                            .get(&(validation_idx + 1, spring_idx + group_len + 1))
-                            .unwrap();
-                        if tail.matches(&desired) {
-                            *after
-                        } else {
-                            0
-                        }
-                    } + if_skip;
-                    spring_arrangements_cache.insert(key, to_insert);
+                    C::zero()
                 }
+            })
+            .collect()
+    }
+
+    /// Counts arrangements one group at a time, from the last, keeping only the counts for the
+    /// current and following group: O(springs * groups) time.
+    pub fn count_arrangements<C: Count>(&self) -> Result<C, Overflow> {
+        let mut next: Vec<C> = self.last_row();
+        let mut row = vec![C::zero(); self.len() + 1];
+        for j in (0..self.groups.len()).rev() {
+            row[self.len()] = C::zero();
+            for i in (0..self.len()).rev() {
+                row[i] = self.ways(i, j, &row, &next)?;
             }
+            std::mem::swap(&mut row, &mut next);
         }
-        *(spring_arrangements_cache.get(&(0, 0)).unwrap())
+        Ok(next.swap_remove(0))
     }
 }
 
-#[derive(Debug)]
-struct SpringField(Vec<Island>);
-
-impl FromIterator<Spring> for SpringField {
-    fn from_iter<T: IntoIterator<Item = Spring>>(iter: T) -> Self {
-        let mut islands: Vec<Island> = Vec::new();
-        let mut island: Vec<Spring> = Vec::new();
-        for spring in iter {
-            if spring != Spring::Working {
-                island.push(spring);
-            } else if !island.is_empty() {
-                islands.push(Island(take(&mut island)));
+/// Every way to fill in the unknown springs of a record, counted for each suffix so that single
+/// arrangements can be picked out without listing the others.
+#[derive(Debug, Clone)]
+pub struct Arrangements {
+    record: Record,
+    /// `ways[j][i]` is the number of arrangements of `springs[i..]` matching `groups[j..]`.
+    ways: Vec<Vec<u64>>,
+}
+
+#[allow(dead_code)]
+impl Arrangements {
+    pub fn new(record: Record) -> Result<Self, Overflow> {
+        let mut ways = vec![record.last_row()];
+        for j in (0..record.groups.len()).rev() {
+            let mut row = vec![0; record.len() + 1];
+            for i in (0..record.len()).rev() {
+                row[i] = record.ways(i, j, &row, ways.last().unwrap())?;
             }
+            ways.push(row);
+        }
+        ways.reverse();
+        Ok(Arrangements { record, ways })
+    }
+
+    /// Parses one record, such as `???.### 1,1,3`.
+    pub fn of_record(line: &str) -> Result<Self, InputError> {
+        let mut records = parse(line)?;
+        match (records.next(), records.next()) {
+            (Some(record), None) => Self::new(record).map_err(|err| overflow_error(line, 0, err)),
+            _ => Err(InputError::at(line, 2, 1, "a single record")),
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.ways[0][0]
+    }
+
+    /// The `k`th arrangement in lexicographic order, with working springs before broken ones, or
+    /// `None` if there are `k` or fewer.
+    pub fn nth(&self, mut k: u64) -> Option<Vec<Spring>> {
+        if k >= self.count() {
+            return None;
         }
-        if !island.is_empty() {
-            islands.push(Island(island));
+        let record = &self.record;
+        let mut arrangement = Vec::with_capacity(record.len());
+        let (mut i, mut j) = (0, 0);
+        while i < record.len() {
+            let working = if record.can_work(i, i + 1) {
+                self.ways[j][i + 1]
+            } else {
+                0
+            };
+            if k < working {
+                arrangement.push(Spring::Working);
+                i += 1;
+                continue;
+            }
+            k -= working;
+            let end = record.group_end(i, j).unwrap();
+            arrangement.extend(vec![Spring::Broken; record.groups[j]]);
+            arrangement.extend(vec![Spring::Working; end - i - record.groups[j]]);
+            i = end;
+            j += 1;
         }
-        SpringField(islands)
+        Some(arrangement)
     }
-}
 
-impl SpringField {
-    fn count_spring_arrangements(&self, validation: Vec<usize>) -> u64 {
-        let mut spring_arrangements_cache: HashMap<(usize, usize), u64> = HashMap::new();
-        for validation_idx in (0..=validation.len()).rev() {
-            spring_arrangements_cache.insert(
-                (validation_idx, self.0.len()),
-                if validation_idx == validation.len() {
-                    1
-                } else {
-                    0
-                },
-            );
-            for island_idx in (0..self.0.len()).rev() {
-                let key = (validation_idx, island_idx);
-                let to_insert = (validation_idx..=(validation.len()))
-                    .map(|next_validation_idx| {
-                        let island_count = self.0[island_idx].count_spring_arrangements(
-                            validation[validation_idx..next_validation_idx]
-                                .iter()
-                                .copied()
-                                .collect(),
-                        );
-                        let next_count = spring_arrangements_cache
-                            .get(&(next_validation_idx, island_idx + 1))
-                            .copied()
-                            .unwrap_or(0);
-                        island_count * next_count
-                    })
-                    .sum();
-                spring_arrangements_cache.insert(key, to_insert);
+    /// Every arrangement in lexicographic order, each built only when it is reached.
+    pub fn iter(&self) -> impl Iterator<Item = Vec<Spring>> + '_ {
+        (0..self.count()).map(|k| self.nth(k).unwrap())
+    }
+
+    /// An arrangement chosen uniformly at random, using `random` as a source of uniform `u64`s, or
+    /// `None` if there are no arrangements.
+    pub fn random(&self, mut random: impl FnMut() -> u64) -> Option<Vec<Spring>> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+        // Draws that fall in the final partial block of `count` values would favour low `k`.
+        let limit = u64::MAX - u64::MAX % count;
+        loop {
+            let draw = random();
+            if draw < limit {
+                return self.nth(draw % count);
             }
         }
-        *(spring_arrangements_cache.get(&(0, 0)).unwrap())
-    }
-
-    // fn count_spring_arrangements_old(&self, validation: Vec<usize>) -> u64 {
-    //     println!("{:?} {:?}", self, validation);
-    //     if self.0.is_empty() {
-    //         if validation.is_empty() {
-    //             return 1;
-    //         } else {
-    //             return 0;
-    //         }
-    //     } else if self.0.len() == 1 {
-    //         return self.0[0].count_spring_arrangements(validation);
-    //     }
-    //     let mut total_count = 0;
-    //     let rest_self = SpringField(self.0.iter().skip(1).cloned().collect());
-    //     for count in 0..=validation.len() {
-    //         let first: Vec<usize> = validation[0..count].iter().copied().collect();
-    //         let first_island_count = self.0[0].count_spring_arrangements(first);
-    //         if first_island_count > 0 {
-    //             let rest_count = rest_self
-    //                 .count_spring_arrangements(validation[count..].iter().copied().collect());
-    //             total_count += first_island_count * rest_count;
-    //         }
-    //     }
-    //     total_count
-    // }
+    }
 }
 
-fn parse(input: &str, duplicate_count: usize) -> impl Iterator<Item = (SpringField, Vec<usize>)> {
-    parsers::tag_replace(".", Spring::Working)
-        .or(parsers::tag_replace("#", Spring::Broken))
-        .or(parsers::tag_replace("?", Spring::Unknown))
-        .many()
-        .map(|i| {
-            let springs: Vec<Spring> = i.collect();
-            let total_count = springs.len() * duplicate_count + duplicate_count - 1;
-            springs
-                .into_iter()
-                .chain(vec![Spring::Unknown])
-                .cycle()
-                .take(total_count)
-                .collect()
+fn overflow_error(input: &str, line_idx: usize, err: Overflow) -> InputError {
+    InputError::at(
+        input,
+        line_idx + 1,
+        1,
+        &format!("a record that can be counted ({})", err),
+    )
+}
+
+/// The total arrangements of every record in `input` unfolded `factor` times with `joiner`, counted
+/// in `C`.
+pub fn count_unfolded<C: Count + Send>(
+    input: &str,
+    factor: usize,
+    joiner: Spring,
+) -> Result<C, InputError> {
+    let records: Vec<Record> = parse(input)?.collect();
+    #[cfg(not(feature = "parallel"))]
+    let records = records.into_iter();
+    #[cfg(feature = "parallel")]
+    let records = records.into_par_iter();
+    let counts: Vec<Result<C, Overflow>> = records
+        .map(|record| record.unfold(factor, joiner).count_arrangements())
+        .collect();
+    counts
+        .into_iter()
+        .enumerate()
+        .try_fold(C::zero(), |total, (line_idx, count)| {
+            count
+                .and_then(|count| total.checked_add(&count).ok_or(Overflow))
+                .map_err(|err| overflow_error(input, line_idx, err))
         })
-        .skip_tag(" ")
-        .and_then(parsers::number().list(",").map(|l| {
-            let validation: Vec<usize> = l.collect();
-            let total_count = validation.len() * duplicate_count;
-            validation.into_iter().cycle().take(total_count).collect()
-        }))
-        .many_lines("\n")
-        .parse(input)
-        .finish()
-        .expect("Failed to parse input")
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> u64 {
-    parse(input, 1)
-        .map(|(springs, validation)| springs.count_spring_arrangements(validation))
-        .sum()
+pub fn part1(input: &str) -> Result<u64, InputError> {
+    count_unfolded(input, 1, Spring::Unknown)
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> u64 {
-    parse(input, 5)
-        .map(|(springs, validation)| springs.count_spring_arrangements(validation))
-        .enumerate()
-        .map(|(idx, v)| {
-            println!("{} {}", idx, v);
-            v
-        })
-        .sum()
+pub fn part2(input: &str) -> Result<u64, InputError> {
+    count_unfolded(input, 5, Spring::Unknown)
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    fn name(&self) -> &'static str {
+        "Hot Springs"
+    }
+
+    fn day(&self) -> u32 {
+        12
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
+    }
+
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
+    }
 }
 
 #[allow(dead_code)]
 #[cfg(test)]
 mod tests {
     use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const EXAMPLE: &str = "???.### 1,1,3
-.??..??...?##. 1,1,3
-?#?#?#?#?#?#?#? 1,3,1,6
-????.#...#... 4,1,1
-????.######..#####. 1,6,5
-?###???????? 3,2,1
-";
-    const DAY: Day = Day::Day12;
+    use crate::test_rng::Lcg;
 
-    #[test]
-    fn simple_example() {
-        assert_eq!(
-            part1(
-                ".?.????.?..??#???# 2,1,1,1,1,2
-"
-            ),
-            1
-        );
+    fn springs(record: &str) -> Vec<Spring> {
+        parse(&format!("{} 1\n", record))
+            .unwrap()
+            .next()
+            .unwrap()
+            .springs
     }
 
     #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 21);
+    fn counts_per_record() {
+        let example = include_str!("../examples/12/example.in");
+        for (duplicate_count, expected) in [
+            (1, [1, 4, 1, 1, 4, 10]),
+            (5, [1, 16384, 1, 16, 2500, 506250]),
+        ] {
+            let counts: Vec<u64> = parse(example)
+                .unwrap()
+                .map(|record| {
+                    record
+                        .unfold(duplicate_count, Spring::Unknown)
+                        .count_arrangements()
+                        .unwrap()
+                })
+                .collect();
+            assert_eq!(counts, expected);
+        }
     }
 
+    /// Checks the counts against trying every assignment of the unknown springs.
     #[test]
-    fn part2_example() {
-        assert_eq!(part2(EXAMPLE), 525152);
+    fn counts_match_brute_force() {
+        let mut rng = Lcg::new(12);
+        for _ in 0..200 {
+            let mut next = |n: u64| rng.below(n);
+            let len = 1 + next(12) as usize;
+            let springs: Vec<Spring> = (0..len)
+                .map(|_| [Spring::Working, Spring::Broken, Spring::Unknown][next(3) as usize])
+                .collect();
+            let groups: Vec<usize> = (0..next(4)).map(|_| 1 + next(3) as usize).collect();
+            let unknown: Vec<usize> = (0..len)
+                .filter(|&i| springs[i] == Spring::Unknown)
+                .collect();
+            let brute_force = (0..1u32 << unknown.len())
+                .filter(|mask| {
+                    let mut filled = springs.clone();
+                    for (bit, &i) in unknown.iter().enumerate() {
+                        filled[i] = if mask & (1 << bit) != 0 {
+                            Spring::Broken
+                        } else {
+                            Spring::Working
+                        };
+                    }
+                    let found: Vec<usize> = render(&filled)
+                        .split('.')
+                        .filter(|run| !run.is_empty())
+                        .map(str::len)
+                        .collect();
+                    found == groups
+                })
+                .count() as u64;
+            let record = Record::new(springs.clone(), groups.clone());
+            assert_eq!(
+                record.count_arrangements::<u64>(),
+                Ok(brute_force),
+                "{} {:?}",
+                render(&springs),
+                groups
+            );
+            assert_eq!(Arrangements::new(record).unwrap().count(), brute_force);
+        }
     }
 
     #[test]
-    fn part1_test() {
+    fn unfolds_with_any_joiner() {
+        let record = parse("???.### 1,1,3\n").unwrap().next().unwrap();
+        let unfolded = record.unfold(3, Spring::Working);
+        assert_eq!(render(&unfolded.springs), "???.###.???.###.???.###");
+        assert_eq!(unfolded.groups, vec![1, 1, 3, 1, 1, 3, 1, 1, 3]);
+        assert_eq!(unfolded.count_arrangements(), Ok(1u64));
+        assert_eq!(
+            record.unfold(5, Spring::Unknown).count_arrangements(),
+            Ok(1u64)
+        );
         assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            7843
+            record.unfold(0, Spring::Unknown).count_arrangements(),
+            Ok(1u64)
+        );
+        // A broken joiner lengthens the last group of each copy but the last.
+        assert_eq!(
+            record.unfold(2, Spring::Broken).count_arrangements(),
+            Ok(0u64)
+        );
+        assert_eq!(
+            count_unfolded::<u64>(".??..??...?##. 1,1,3\n", 2, Spring::Working),
+            Ok(16)
         );
     }
 
     #[test]
-    fn part2_test() {
+    fn detects_overflow() {
+        // 20 broken springs placed apart among 209: C(190, 20) arrangements, about 2^89.
+        let input = "???????????????????? 1,1\n";
+        let record = parse(input).unwrap().next().unwrap();
+        let unfolded = record.unfold(10, Spring::Unknown);
+        assert_eq!(unfolded.count_arrangements::<u64>(), Err(Overflow));
         assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            10153896718999
+            unfolded.count_arrangements::<u128>(),
+            Ok(548255784159901541393350065)
         );
+        assert_eq!(
+            record
+                .unfold(40, Spring::Unknown)
+                .count_arrangements::<u128>(),
+            Err(Overflow)
+        );
+        let error =
+            count_unfolded::<u64>(&format!("# 1\n{}", input), 10, Spring::Unknown).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert!(error.expected.contains("too many arrangements"));
+        #[cfg(feature = "bigint")]
+        {
+            let big: num_bigint::BigUint = record
+                .unfold(40, Spring::Unknown)
+                .count_arrangements()
+                .unwrap();
+            assert!(big.bits() > 128);
+        }
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
-    }
-
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    #[test]
+    fn lists_arrangements_in_order() {
+        let arrangements = Arrangements::of_record("?###???????? 3,2,1").unwrap();
+        assert_eq!(arrangements.count(), 10);
+        let listed: Vec<String> = arrangements.iter().map(|a| render(&a)).collect();
+        assert_eq!(listed.len(), 10);
+        assert_eq!(listed[0], ".###....##.#");
+        assert_eq!(listed[9], ".###.##.#...");
+        let mut sorted = listed.clone();
+        sorted.sort_by_key(|a| springs(a));
+        assert_eq!(listed, sorted);
+        sorted.dedup();
+        assert_eq!(sorted.len(), 10);
+        for arrangement in &listed {
+            assert_eq!(
+                Arrangements::of_record(&format!("{} 3,2,1", arrangement))
+                    .unwrap()
+                    .count(),
+                1
+            );
+        }
+        assert_eq!(arrangements.nth(10), None);
+    }
+
+    #[test]
+    fn random_arrangements() {
+        let arrangements = Arrangements::of_record("?###???????? 3,2,1").unwrap();
+        let mut rng = Lcg::new(12);
+        let mut next = || rng.next_u64();
+        let mut seen = vec![0; 10];
+        for _ in 0..1000 {
+            let arrangement = arrangements.random(&mut next).unwrap();
+            let k = arrangements.iter().position(|a| a == arrangement).unwrap();
+            seen[k] += 1;
+        }
+        assert!(seen.iter().all(|&n| n > 50), "{:?}", seen);
+        assert_eq!(Arrangements::of_record("# 2").unwrap().random(next), None);
     }
 }
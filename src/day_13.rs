@@ -5,23 +5,36 @@ use advent_of_code::{
 
 use std::iter::zip;
 
+use crate::{
+    input::InputError,
+    solution::{Answer, Solution},
+};
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Cell {
     Ash,
     Rock,
 }
 
-fn parse(input: &str) -> impl Iterator<Item = Grid<Cell>> {
-    parsers::tag_replace(".", Cell::Ash)
+const EXPECTED: &str = "`.` or `#`";
+
+fn parse(input: &str) -> Result<impl Iterator<Item = Grid<Cell>>, InputError> {
+    let patterns = parsers::tag_replace(".", Cell::Ash)
         .or(parsers::tag_replace("#", Cell::Rock))
         .many_at_least_one()
         .map(|i| i.collect::<Vec<Cell>>())
         .many_lines("\n")
-        .map(|i| Grid::of_vec_of_vecs(i.collect()).unwrap())
+        .map(|i| i.collect::<Vec<Vec<Cell>>>())
         .list("\n")
         .parse(input)
         .finish()
-        .expect("Failed to parse input")
+        .map_err(|_| InputError::in_grid(input, EXPECTED, |c| c == '.' || c == '#'))?;
+    let grids = patterns
+        .map(|rows| {
+            Grid::of_vec_of_vecs(rows).map_err(|_| InputError::in_grid(input, EXPECTED, |_| true))
+        })
+        .collect::<Result<Vec<Grid<Cell>>, InputError>>()?;
+    Ok(grids.into_iter())
 }
 
 // 0 - (4 + 0) / 2 = 2
@@ -73,105 +86,40 @@ fn smudge_symmetry_idx(rows: Vec<Vec<Cell>>) -> u32 {
     return 0;
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> u32 {
-    parse(input)
+pub fn part1(input: &str) -> Result<u32, InputError> {
+    Ok(parse(input)?
         .map(|grid| 100 * symmetry_idx(grid.all_rows()) + symmetry_idx(grid.all_cols()))
-        .sum()
+        .sum())
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> u32 {
-    parse(input)
+pub fn part2(input: &str) -> Result<u32, InputError> {
+    Ok(parse(input)?
         .map(|grid| {
             100 * smudge_symmetry_idx(grid.all_rows()) + smudge_symmetry_idx(grid.all_cols())
         })
-        .sum()
+        .sum())
 }
 
-#[allow(dead_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const EXAMPLE: &str = "#.##..##.
-..#.##.#.
-##......#
-##......#
-..#.##.#.
-..##..##.
-#.#.##.#.
-
-#...##..#
-#....#..#
-..##..###
-#####.##.
-#####.##.
-..##..###
-#....#..#
-";
-    const DAY: Day = Day::Day13;
-
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 405);
-    }
+pub struct Day13;
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(EXAMPLE), 400);
+impl Solution for Day13 {
+    fn name(&self) -> &'static str {
+        "Point of Incidence"
     }
 
-    #[test]
-    fn part1_test() {
-        assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            37381
-        );
+    fn day(&self) -> u32 {
+        13
     }
 
-    #[test]
-    fn part2_test() {
-        assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            28210
-        );
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
     }
 }
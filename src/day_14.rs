@@ -1,61 +1,127 @@
-use std::ops::Add;
+use std::{collections::HashMap, ops::Add};
 
 use advent_of_code::{
     grid::{Grid, GridPoint, GridPointDelta, EAST, NORTH, SOUTH, WEST},
     parse::{parsers, Parser},
 };
 
+use crate::{
+    input::InputError,
+    solution::{Answer, Solution},
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Cell {
+pub enum Cell {
     Round,
     Square,
     Empty,
 }
 
-fn parse(input: &str) -> Grid<Cell> {
-    parsers::tag_replace("#", Cell::Square)
+/// A way to tilt the platform, which is also the edge the rocks roll towards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    West,
+    South,
+    East,
+}
+
+impl Direction {
+    fn delta(self) -> GridPointDelta<isize> {
+        match self {
+            Self::North => NORTH,
+            Self::West => WEST,
+            Self::South => SOUTH,
+            Self::East => EAST,
+        }
+    }
+}
+
+const EXPECTED: &str = "one of `O`, `#`, `.`";
+
+fn parse(input: &str) -> Result<Grid<Cell>, InputError> {
+    let rows: Vec<Vec<Cell>> = parsers::tag_replace("#", Cell::Square)
         .or(parsers::tag_replace("O", Cell::Round))
         .or(parsers::tag_replace(".", Cell::Empty))
         .many()
         .map(|i| i.collect())
         .many_lines("\n")
-        .map(|i| Grid::of_vec_of_vecs(i.collect()).unwrap())
+        .map(|i| i.collect())
         .parse(input)
         .finish()
-        .expect("Failed to parse input")
+        .map_err(|_| InputError::in_grid(input, EXPECTED, |c| "O#.".contains(c)))?;
+    let grid = Grid::of_vec_of_vecs(rows)
+        .map_err(|_| InputError::in_grid(input, EXPECTED, |c| "O#.".contains(c)))?;
+    match far_corner(&grid) {
+        Some(_) => Ok(grid),
+        None => Err(InputError::at(
+            input,
+            1,
+            1,
+            "a platform with at least one row and column",
+        )),
+    }
 }
 
-fn evaluate_north_weight(grid: Grid<Cell>) -> u32 {
-    grid.all_cols()
-        .into_iter()
-        .map(|col| {
-            col.into_iter()
-                .rev()
-                .enumerate()
-                .fold(0, |total_load, (current_distance, cell)| match cell {
-                    Cell::Round => total_load + (current_distance as u32) + 1,
-                    Cell::Empty => total_load,
-                    Cell::Square => total_load,
-                })
-        })
+/// The bottom-right cell of the platform, or `None` if it has no cells.
+fn far_corner(grid: &Grid<Cell>) -> Option<GridPoint<usize>> {
+    Some(GridPoint::new(
+        grid.rows().checked_sub(1)?,
+        grid.cols().checked_sub(1)?,
+    ))
+}
+
+/// Where every round rock is, row by row.
+pub fn round_rock_positions(grid: &Grid<Cell>) -> Vec<GridPoint<usize>> {
+    grid.iter_points()
+        .filter(|&point| grid.get(point).unwrap() == &Cell::Round)
+        .collect()
+}
+
+/// The load of `rocks` on the support beams at the `direction` edge of a platform: each rock weighs
+/// its distance from the opposite edge, counting its own row or column.
+fn rock_load(
+    rocks: &[GridPoint<usize>],
+    (rows, cols): (usize, usize),
+    direction: Direction,
+) -> u32 {
+    rocks
+        .iter()
+        .map(|rock| match direction {
+            Direction::North => rows - rock.row(),
+            Direction::South => rock.row() + 1,
+            Direction::West => cols - rock.col(),
+            Direction::East => rock.col() + 1,
+        } as u32)
         .sum()
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> u32 {
-    let mut grid = parse(input);
-    move_rocks(&mut grid, NORTH);
-    evaluate_north_weight(grid)
+/// The load on the support beams at the `direction` edge of the platform.
+pub fn load(grid: &Grid<Cell>, direction: Direction) -> u32 {
+    rock_load(
+        &round_rock_positions(grid),
+        (grid.rows(), grid.cols()),
+        direction,
+    )
 }
 
-fn move_rocks(grid: &mut Grid<Cell>, direction: GridPointDelta<isize>) {
+pub fn part1(input: &str) -> Result<u32, InputError> {
+    let mut grid = parse(input)?;
+    move_rocks(&mut grid, Direction::North);
+    Ok(load(&grid, Direction::North))
+}
+
+fn move_rocks(grid: &mut Grid<Cell>, direction: Direction) {
+    let Some(far) = far_corner(grid) else {
+        return;
+    };
     let (initial_corner, secondary_direction) = match direction {
-        NORTH => (GridPoint::new(0, 0), EAST),
-        WEST => (GridPoint::new(0, 0), SOUTH),
-        SOUTH => (GridPoint::new(grid.rows() - 1, 0), EAST),
-        EAST => (GridPoint::new(0, grid.cols() - 1), SOUTH),
-        _ => panic!("unrecognize direction {:?}", direction),
+        Direction::North => (GridPoint::new(0, 0), EAST),
+        Direction::West => (GridPoint::new(0, 0), SOUTH),
+        Direction::South => (GridPoint::new(far.row(), 0), EAST),
+        Direction::East => (GridPoint::new(0, far.col()), SOUTH),
     };
+    let direction = direction.delta();
     let grid_dimensions = grid.dimensions();
     for initial_point in initial_corner.traverse_by(secondary_direction, grid_dimensions) {
         let mut next_empty_space = initial_point;
@@ -75,111 +141,240 @@ fn move_rocks(grid: &mut Grid<Cell>, direction: GridPointDelta<isize>) {
     }
 }
 
-fn cycle(grid: &mut Grid<Cell>) {
-    move_rocks(grid, NORTH);
-    move_rocks(grid, WEST);
-    move_rocks(grid, SOUTH);
-    move_rocks(grid, EAST);
+const TILT_EXPECTED: &str = "one of `N`, `W`, `S`, `E`";
+
+/// Reads a tilt sequence such as `"NWSE"`. Whitespace is ignored, so `"NN E"` is the same as
+/// `"NNE"`.
+pub fn parse_tilts(sequence: &str) -> Result<Vec<Direction>, InputError> {
+    let valid = |c: char| "NWSE".contains(c) || c.is_whitespace();
+    sequence
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            'N' => Ok(Direction::North),
+            'W' => Ok(Direction::West),
+            'S' => Ok(Direction::South),
+            'E' => Ok(Direction::East),
+            _ => Err(InputError::invalid_char(sequence, TILT_EXPECTED, valid)),
+        })
+        .collect()
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> u32 {
-    let mut seen = vec![];
-    let mut grid = parse(input);
-    seen.push(grid.clone());
-    let full_iter_count = 1_000_000_000;
-    for idx in 1..full_iter_count {
-        cycle(&mut grid);
-        if let Some(seen_idx) = seen.iter().position(|g| g == &grid) {
-            let cycle_length = idx - seen_idx;
-            let desired_idx_offset = (full_iter_count - idx) % cycle_length + seen_idx;
-            grid = seen[desired_idx_offset].clone();
-            break;
+/// The loads seen while repeating a tilt sequence until the platform returns to an earlier state.
+/// Step 0 is the platform before any tilting and step `n` is after `n` rounds of the sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TiltCycle {
+    /// The first step of the repeating part.
+    pub start: usize,
+    /// The number of steps after which the platform repeats itself.
+    pub length: usize,
+    loads: Vec<u32>,
+}
+
+impl TiltCycle {
+    /// The load on the north support beams after `step` rounds of the sequence.
+    pub fn load_at(&self, step: usize) -> u32 {
+        if step < self.start {
+            self.loads[step]
         } else {
-            seen.push(grid.clone());
+            self.loads[self.start + (step - self.start) % self.length]
+        }
+    }
+}
+
+/// The platform's state as a bitset of round rocks, row by row; square rocks never move.
+fn round_rocks(grid: &Grid<Cell>) -> Vec<u64> {
+    let mut bits = vec![0; (grid.rows() * grid.cols()).div_ceil(64)];
+    for (idx, point) in grid.iter_points().enumerate() {
+        if grid.get(point).unwrap() == &Cell::Round {
+            bits[idx / 64] |= 1 << (idx % 64);
         }
     }
-    evaluate_north_weight(grid)
+    bits
+}
+
+/// Repeats `tilts` on the platform until a state repeats, recognizing earlier states by hash.
+fn find_cycle(mut grid: Grid<Cell>, tilts: &[Direction]) -> TiltCycle {
+    let mut seen: HashMap<Vec<u64>, usize> = HashMap::new();
+    let mut loads = vec![];
+    loop {
+        let state = round_rocks(&grid);
+        if let Some(&start) = seen.get(&state) {
+            return TiltCycle {
+                start,
+                length: loads.len() - start,
+                loads,
+            };
+        }
+        seen.insert(state, loads.len());
+        loads.push(load(&grid, Direction::North));
+        for &direction in tilts {
+            move_rocks(&mut grid, direction);
+        }
+    }
+}
+
+/// The platform after one tilt of a sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TiltStep {
+    /// The round of the sequence the tilt belongs to, counting from 1.
+    pub round: usize,
+    pub direction: Direction,
+    /// Where every round rock ended up, row by row.
+    pub rocks: Vec<GridPoint<usize>>,
+    /// The platform's rows and columns.
+    size: (usize, usize),
+}
+
+#[allow(dead_code)]
+impl TiltStep {
+    /// The load on the support beams at the `direction` edge after this tilt.
+    pub fn load(&self, direction: Direction) -> u32 {
+        rock_load(&self.rocks, self.size, direction)
+    }
+}
+
+/// Every tilt of `rounds` rounds of `sequence` on the platform in `input`, for following how the
+/// rocks and loads move from tilt to tilt.
+#[allow(dead_code)]
+pub fn trajectory(input: &str, sequence: &str, rounds: usize) -> Result<Vec<TiltStep>, InputError> {
+    let tilts = parse_tilts(sequence)?;
+    let mut grid = parse(input)?;
+    let mut steps = Vec::with_capacity(rounds * tilts.len());
+    for round in 1..=rounds {
+        for &direction in &tilts {
+            move_rocks(&mut grid, direction);
+            steps.push(TiltStep {
+                round,
+                direction,
+                rocks: round_rock_positions(&grid),
+                size: (grid.rows(), grid.cols()),
+            });
+        }
+    }
+    Ok(steps)
+}
+
+/// Finds the cycle of the platform in `input` under the tilt sequence `sequence`.
+pub fn spin(input: &str, sequence: &str) -> Result<TiltCycle, InputError> {
+    let tilts = parse_tilts(sequence)?;
+    Ok(find_cycle(parse(input)?, &tilts))
+}
+
+pub fn part2(input: &str) -> Result<u32, InputError> {
+    Ok(spin(input, "NWSE")?.load_at(1_000_000_000))
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    fn name(&self) -> &'static str {
+        "Parabolic Reflector Dish"
+    }
+
+    fn day(&self) -> u32 {
+        14
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
+    }
+
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
+    }
 }
 
 #[allow(dead_code)]
 #[cfg(test)]
 mod tests {
     use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const EXAMPLE: &str = "O....#....
-O.OO#....#
-.....##...
-OO.#O....O
-.O.....O#.
-O.#..O.#.#
-..O..#O..O
-.......O..
-#....###..
-#OO..#....
-";
-    const DAY: Day = Day::Day14;
 
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 136);
-    }
+    const EXAMPLE: &str = include_str!("../examples/14/example.in");
 
     #[test]
-    fn part2_example() {
-        assert_eq!(part2(EXAMPLE), 64);
+    fn spin_cycle() {
+        let cycle = spin(EXAMPLE, "NWSE").unwrap();
+        assert_eq!((cycle.start, cycle.length), (3, 7));
+        assert_eq!(cycle.load_at(0), 104);
+        assert_eq!(cycle.load_at(1), 87);
+        assert_eq!(cycle.load_at(1_000_000_000), 64);
+        assert_eq!(cycle.load_at(3 + 7 * 1000), cycle.load_at(3));
     }
 
     #[test]
-    fn part1_test() {
+    fn loads_and_rocks() {
+        let grid = parse(EXAMPLE).unwrap();
+        assert_eq!(load(&grid, Direction::North), 104);
+        let rocks = round_rock_positions(&grid);
+        assert_eq!(rocks.len(), 18);
+        assert_eq!(rocks[0], GridPoint::new(0, 0));
+        // Turning the platform upside down swaps the north and south loads.
+        let flipped = parse(
+            &EXAMPLE
+                .lines()
+                .rev()
+                .map(|line| format!("{}\n", line))
+                .collect::<String>(),
+        )
+        .unwrap();
+        assert_eq!(
+            load(&flipped, Direction::South),
+            load(&grid, Direction::North)
+        );
         assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            108759
+            load(&flipped, Direction::North),
+            load(&grid, Direction::South)
         );
+
+        let steps = trajectory(EXAMPLE, "NWSE", 3).unwrap();
+        assert_eq!(steps.len(), 12);
+        assert_eq!((steps[0].round, steps[0].direction), (1, Direction::North));
+        assert_eq!(steps[0].load(Direction::North), part1(EXAMPLE).unwrap());
+        assert_eq!((steps[11].round, steps[11].direction), (3, Direction::East));
+        assert_eq!(
+            steps[3].load(Direction::North),
+            spin(EXAMPLE, "NWSE").unwrap().load_at(1)
+        );
+        assert!(steps.iter().all(|step| step.rocks.len() == 18));
+        // After tilting east, every rock rests against a wall or another rock to its east.
+        let east = &steps[3];
+        for rock in &east.rocks {
+            let neighbour = GridPoint::new(rock.row(), rock.col() + 1);
+            assert!(
+                rock.col() == 9
+                    || east.rocks.contains(&neighbour)
+                    || matches!(grid.get(neighbour), Ok(Cell::Square))
+            );
+        }
     }
 
     #[test]
-    fn part2_test() {
+    fn other_sequences() {
+        let north = spin(EXAMPLE, "N").unwrap();
+        assert_eq!((north.start, north.length), (1, 1));
+        assert_eq!(north.load_at(5), part1(EXAMPLE).unwrap());
         assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            89089
+            spin(EXAMPLE, "NN E").unwrap(),
+            spin(EXAMPLE, "NNE").unwrap()
         );
+        let still = spin(EXAMPLE, "").unwrap();
+        assert_eq!((still.start, still.length), (0, 1));
+        assert_eq!(still.load_at(10), 104);
+        let error = spin(EXAMPLE, "NWX").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 3));
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
-    }
-
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    #[test]
+    fn empty_platform() {
+        for input in ["", "\n"] {
+            let error = part1(input).unwrap_err();
+            assert_eq!((error.line, error.column), (1, 1));
+            assert!(part2(input).is_err());
+        }
     }
 }
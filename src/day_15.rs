@@ -1,5 +1,10 @@
 use advent_of_code::parse::{parsers, Parser};
 
+use crate::{
+    input::{InputError, InputLines},
+    solution::{Answer, Solution},
+};
+
 fn hash(s: &str) -> u32 {
     s.bytes()
         .into_iter()
@@ -7,16 +12,13 @@ fn hash(s: &str) -> u32 {
         .fold(0, |v, b| (17 * (v + (b as u32))) % 256)
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> u32 {
-    parsers::many_chars(|c| c != ',')
-        .list(",")
-        .skip_tag("\n")
-        .parse(input)
-        .finish()
-        .expect("Failed to parse input")
-        .map(|s| hash(&s))
-        .sum()
+pub fn part1(input: &str) -> Result<u32, InputError> {
+    let mut lines = InputLines::new(input);
+    let sum = lines.next_line("a comma-separated list of steps", |line| {
+        Ok(line.split(",").map(|step| hash(step.text())).sum())
+    })?;
+    lines.finish()?;
+    Ok(sum)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -25,42 +27,49 @@ enum Operation {
     Remove,
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> u32 {
+fn parse_steps(input: &str) -> Result<Vec<(String, Operation)>, InputError> {
+    let mut lines = InputLines::new(input);
+    let steps = lines.next_line("a step like `rn=1` or `cm-`", |line| {
+        line.split(",")
+            .map(|step| {
+                step.parse(
+                    parsers::many_chars(|c| c != '=' && c != '-').and_then(
+                        parsers::tag_replace("-", Operation::Remove).or(parsers::tag("=")
+                            .ignore_and_then(parsers::number())
+                            .map(|n| Operation::Add(n))),
+                    ),
+                )
+            })
+            .collect()
+    })?;
+    lines.finish()?;
+    Ok(steps)
+}
+
+pub fn part2(input: &str) -> Result<u32, InputError> {
     let mut boxes: Vec<Vec<(String, u32)>> = (0..256).map(|_| Vec::new()).collect();
 
-    parsers::many_chars(|c| c != '=' && c != '-')
-        .and_then(
-            parsers::tag_replace("-", Operation::Remove).or(parsers::tag("=")
-                .ignore_and_then(parsers::number())
-                .map(|n| Operation::Add(n))),
-        )
-        .list(",")
-        .skip_tag("\n")
-        .parse(input)
-        .finish()
-        .expect("Failed to parse input")
-        .for_each(|(label, op)| {
-            let idx = hash(&label) as usize;
-            match op {
-                Operation::Remove => boxes[idx]
-                    .iter()
-                    .position(|(s, _)| s == &label)
-                    .into_iter()
-                    .for_each(|to_remove| {
-                        boxes[idx].remove(to_remove);
-                    }),
-                Operation::Add(n) => {
-                    if let Some(jdx) = boxes[idx].iter().position(|(s, _)| s == &label) {
-                        boxes[idx][jdx] = (label.clone(), n)
-                    } else {
-                        boxes[idx].push((label.clone(), n))
-                    }
+    parse_steps(input)?.into_iter().for_each(|(label, op)| {
+        let idx = hash(&label) as usize;
+        match op {
+            Operation::Remove => boxes[idx]
+                .iter()
+                .position(|(s, _)| s == &label)
+                .into_iter()
+                .for_each(|to_remove| {
+                    boxes[idx].remove(to_remove);
+                }),
+            Operation::Add(n) => {
+                if let Some(jdx) = boxes[idx].iter().position(|(s, _)| s == &label) {
+                    boxes[idx][jdx] = (label.clone(), n)
+                } else {
+                    boxes[idx].push((label.clone(), n))
                 }
             }
-        });
+        }
+    });
 
-    boxes
+    Ok(boxes
         .into_iter()
         .enumerate()
         .map(|(idx, lenses)| {
@@ -71,78 +80,45 @@ pub fn part2(input: &str) -> u32 {
                     .map(|(jdx, (_, focal_length))| (jdx + 1) as u32 * focal_length)
                     .sum::<u32>()
         })
-        .sum()
+        .sum())
 }
 
-#[allow(dead_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
-";
-    const DAY: Day = Day::Day15;
+pub struct Day15;
 
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 1320);
+impl Solution for Day15 {
+    fn name(&self) -> &'static str {
+        "Lens Library"
     }
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(EXAMPLE), 145);
+    fn day(&self) -> u32 {
+        15
     }
 
-    #[test]
-    fn part1_test() {
-        assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            510792
-        );
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
     }
 
-    #[test]
-    fn part2_test() {
-        assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            269410
-        );
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn parse(&self, part: u32, input: &str) -> Option<Result<(), InputError>> {
+        (part == 2).then(|| parse_steps(input).map(drop))
     }
+}
+
+#[allow(dead_code)]
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    #[test]
+    fn malformed_step() {
+        let error = part2("rn=1,cm-,qp=x,cm-\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.snippet.as_str()),
+            (1, 10, "qp=x,cm-")
+        );
+        assert_eq!(part1("rn=1\nqp=3\n").unwrap_err().line, 2);
     }
 }
@@ -1,124 +1,453 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::VecDeque;
 
 use advent_of_code::{
-    grid::{Grid, GridDimensions, GridPoint, GridPointDelta, EAST, NORTH, SOUTH, WEST},
+    grid::{Grid, GridPoint, GridPointDelta, EAST, NORTH, SOUTH, WEST},
     parse::{parsers, Parser},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Cell {
-    Empty,
-    SplitterVertical,
-    SplitterHorizontal,
-    MirrorNE,
-    MirrorNW,
-}
-
-fn parse(input: &str) -> Grid<Cell> {
-    parsers::tag_replace(".", Cell::Empty)
-        .or(parsers::tag_replace("|", Cell::SplitterVertical))
-        .or(parsers::tag_replace("-", Cell::SplitterHorizontal))
-        .or(parsers::tag_replace("/", Cell::MirrorNE))
-        .or(parsers::tag_replace("\\", Cell::MirrorNW))
+use crate::{
+    input::InputError,
+    solution::{Answer, Solution},
+};
+
+/// Something a beam can meet in a grid cell.
+pub trait OpticalElement {
+    /// The directions a beam entering the cell heading in `dir` leaves it in; empty if the beam is
+    /// absorbed.
+    fn deflect(&self, dir: GridPointDelta<isize>) -> &'static [GridPointDelta<isize>];
+}
+
+fn straight(dir: GridPointDelta<isize>) -> &'static [GridPointDelta<isize>] {
+    match dir {
+        NORTH => &[NORTH],
+        EAST => &[EAST],
+        SOUTH => &[SOUTH],
+        WEST => &[WEST],
+        _ => panic!("unrecognized direction"),
+    }
+}
+
+/// `.`: lets the beam through.
+pub struct Empty;
+
+impl OpticalElement for Empty {
+    fn deflect(&self, dir: GridPointDelta<isize>) -> &'static [GridPointDelta<isize>] {
+        straight(dir)
+    }
+}
+
+/// `|`: splits beams travelling east or west into north and south.
+pub struct SplitterVertical;
+
+impl OpticalElement for SplitterVertical {
+    fn deflect(&self, dir: GridPointDelta<isize>) -> &'static [GridPointDelta<isize>] {
+        if dir == EAST || dir == WEST {
+            &[NORTH, SOUTH]
+        } else {
+            straight(dir)
+        }
+    }
+}
+
+/// `-`: splits beams travelling north or south into east and west.
+pub struct SplitterHorizontal;
+
+impl OpticalElement for SplitterHorizontal {
+    fn deflect(&self, dir: GridPointDelta<isize>) -> &'static [GridPointDelta<isize>] {
+        if dir == NORTH || dir == SOUTH {
+            &[EAST, WEST]
+        } else {
+            straight(dir)
+        }
+    }
+}
+
+/// `/`
+pub struct MirrorNE;
+
+impl OpticalElement for MirrorNE {
+    fn deflect(&self, dir: GridPointDelta<isize>) -> &'static [GridPointDelta<isize>] {
+        straight(match dir {
+            NORTH => EAST,
+            EAST => NORTH,
+            SOUTH => WEST,
+            WEST => SOUTH,
+            _ => panic!("unrecognized direction"),
+        })
+    }
+}
+
+/// `\`
+pub struct MirrorNW;
+
+impl OpticalElement for MirrorNW {
+    fn deflect(&self, dir: GridPointDelta<isize>) -> &'static [GridPointDelta<isize>] {
+        straight(match dir {
+            NORTH => WEST,
+            WEST => NORTH,
+            SOUTH => EAST,
+            EAST => SOUTH,
+            _ => panic!("unrecognized direction"),
+        })
+    }
+}
+
+/// Stops every beam.
+#[allow(dead_code)]
+pub struct Absorber;
+
+impl OpticalElement for Absorber {
+    fn deflect(&self, _dir: GridPointDelta<isize>) -> &'static [GridPointDelta<isize>] {
+        &[]
+    }
+}
+
+/// Lets beams travelling in `dir` through and absorbs all others.
+#[allow(dead_code)]
+pub struct Gate {
+    pub dir: GridPointDelta<isize>,
+}
+
+impl OpticalElement for Gate {
+    fn deflect(&self, dir: GridPointDelta<isize>) -> &'static [GridPointDelta<isize>] {
+        if dir == self.dir {
+            straight(dir)
+        } else {
+            &[]
+        }
+    }
+}
+
+/// Turns every beam a quarter turn, clockwise or anticlockwise.
+#[allow(dead_code)]
+pub struct Prism {
+    pub clockwise: bool,
+}
+
+impl OpticalElement for Prism {
+    fn deflect(&self, dir: GridPointDelta<isize>) -> &'static [GridPointDelta<isize>] {
+        let turns = if self.clockwise {
+            1
+        } else {
+            DIRECTIONS.len() - 1
+        };
+        straight(DIRECTIONS[(direction_index(dir) + turns) % DIRECTIONS.len()])
+    }
+}
+
+/// The element each character of an input stands for.
+pub struct Elements<'a> {
+    elements: Vec<(char, &'a dyn OpticalElement)>,
+}
+
+impl<'a> Elements<'a> {
+    /// The five elements of the puzzle.
+    pub fn standard() -> Self {
+        Elements {
+            elements: vec![
+                ('.', &Empty),
+                ('|', &SplitterVertical),
+                ('-', &SplitterHorizontal),
+                ('/', &MirrorNE),
+                ('\\', &MirrorNW),
+            ],
+        }
+    }
+
+    /// Makes `symbol` stand for `element`, replacing whatever it stood for before.
+    #[allow(dead_code)]
+    pub fn register(&mut self, symbol: char, element: &'a dyn OpticalElement) {
+        self.elements.retain(|&(existing, _)| existing != symbol);
+        self.elements.push((symbol, element));
+    }
+
+    fn get(&self, symbol: char) -> Option<&'a dyn OpticalElement> {
+        self.elements
+            .iter()
+            .find(|&&(existing, _)| existing == symbol)
+            .map(|&(_, element)| element)
+    }
+
+    fn expected(&self) -> String {
+        let symbols: Vec<String> = self
+            .elements
+            .iter()
+            .map(|(symbol, _)| format!("`{}`", symbol))
+            .collect();
+        format!("one of {}", symbols.join(", "))
+    }
+}
+
+pub type Contraption<'a> = Grid<&'a dyn OpticalElement>;
+
+/// Parses a grid of the characters registered in `elements`.
+pub fn parse_with<'a>(input: &str, elements: &Elements<'a>) -> Result<Contraption<'a>, InputError> {
+    let known = |c: char| elements.get(c).is_some();
+    let rows: Vec<Vec<&dyn OpticalElement>> = parsers::chars(known)
+        .map(|c| elements.get(c).unwrap())
         .many()
         .map(|i| i.collect())
         .many_lines("\n")
-        .map(|i| Grid::of_vec_of_vecs(i.collect()).unwrap())
+        .map(|i| i.collect())
         .parse(input)
         .finish()
-        .expect("Failed to parse input")
-}
-
-fn queue_next(
-    seen: &mut HashSet<(GridPoint<usize>, GridPointDelta<isize>)>,
-    to_search: &mut VecDeque<(GridPoint<usize>, GridPointDelta<isize>)>,
-    current: GridPoint<usize>,
-    dir: GridPointDelta<isize>,
-    dimensions: &GridDimensions<usize>,
-) {
-    if let Some(next) = current.add_checked(dir, dimensions) {
-        if seen.insert((next, dir)) {
-            to_search.push_back((next, dir));
-        }
+        .map_err(|_| InputError::in_grid(input, &elements.expected(), known))?;
+    let grid = Grid::of_vec_of_vecs(rows)
+        .map_err(|_| InputError::in_grid(input, &elements.expected(), known))?;
+    match far_corner(&grid) {
+        Some(_) => Ok(grid),
+        None => Err(InputError::at(
+            input,
+            1,
+            1,
+            "a contraption with at least one row and column",
+        )),
     }
 }
 
-fn simulate(grid: &Grid<Cell>, start: GridPoint<usize>, start_dir: GridPointDelta<isize>) -> usize {
-    let mut seen: HashSet<(GridPoint<usize>, GridPointDelta<isize>)> = HashSet::new();
+/// The bottom-right tile of the contraption, or `None` if it has no tiles.
+fn far_corner(grid: &Contraption) -> Option<GridPoint<usize>> {
+    Some(GridPoint::new(
+        grid.rows().checked_sub(1)?,
+        grid.cols().checked_sub(1)?,
+    ))
+}
+
+fn parse(input: &str) -> Result<Contraption<'static>, InputError> {
+    parse_with(input, &Elements::standard())
+}
+
+/// The directions a beam can travel in, in the order of their bits in an energization map.
+const DIRECTIONS: [GridPointDelta<isize>; 4] = [NORTH, EAST, SOUTH, WEST];
+
+fn direction_index(dir: GridPointDelta<isize>) -> usize {
+    DIRECTIONS
+        .iter()
+        .position(|&d| d == dir)
+        .expect("unrecognized direction")
+}
+
+/// The bit set in an energization map for a beam entering a cell heading in `dir`.
+pub fn direction_bit(dir: GridPointDelta<isize>) -> u8 {
+    1 << direction_index(dir)
+}
+
+/// Follows a beam entering `start` heading in `start_dir`. Each cell of the result holds the
+/// [`direction_bit`]s of every beam that entered it, so 0 means the cell was never energized.
+pub fn simulate(
+    grid: &Contraption,
+    start: GridPoint<usize>,
+    start_dir: GridPointDelta<isize>,
+) -> Grid<u8> {
+    let grid_dimensions = grid.dimensions();
+    let mut energy = Grid::of_vec_of_vecs(vec![vec![0; grid.cols()]; grid.rows()]).unwrap();
     let mut to_search: VecDeque<(GridPoint<usize>, GridPointDelta<isize>)> = VecDeque::new();
-    seen.insert((start, start_dir));
     to_search.push_back((start, start_dir));
     while let Some((current, dir)) = to_search.pop_front() {
+        let passed = *energy.get(current).unwrap();
+        if passed & direction_bit(dir) != 0 {
+            continue;
+        }
+        energy.set(current, passed | direction_bit(dir)).unwrap();
+        for &next_dir in grid.get(current).unwrap().deflect(dir) {
+            if let Some(next) = current.add_checked(next_dir, &grid_dimensions) {
+                to_search.push_back((next, next_dir));
+            }
+        }
+    }
+    energy
+}
+
+/// The number of cells a beam passed through.
+pub fn energized(energy: &Grid<u8>) -> usize {
+    energy
+        .iter_points()
+        .filter(|&point| *energy.get(point).unwrap() != 0)
+        .count()
+}
+
+/// The cells reachable from one strongly connected component of beam states.
+enum Reach {
+    /// A component of several states, or one that splits the beam, with the set of cells
+    /// energized from it as a bitset.
+    Cells(Vec<u64>),
+    /// A lone state the beam passes straight through: its cell, and the component it leads to.
+    Chain { cell: usize, next: Option<usize> },
+}
+
+/// Every beam state of a grid, numbered `(row * cols + col) * 4 + direction`, with the cells
+/// energized from each strongly connected component memoized. Components are built in one pass
+/// over the states, after which energizing from any start only walks to the nearest memoized
+/// component instead of re-running the whole simulation.
+pub struct BeamGraph {
+    cols: usize,
+    cells: usize,
+    component: Vec<usize>,
+    reach: Vec<Reach>,
+}
+
+impl BeamGraph {
+    pub fn new(grid: &Contraption) -> Self {
         let grid_dimensions = grid.dimensions();
-        match grid.get(current).unwrap() {
-            Cell::Empty => {
-                queue_next(&mut seen, &mut to_search, current, dir, &grid_dimensions);
+        let cols = grid.cols();
+        let cells = grid.rows() * cols;
+        let state = |point: GridPoint<usize>, dir: GridPointDelta<isize>| {
+            (point.row() * cols + point.col()) * DIRECTIONS.len() + direction_index(dir)
+        };
+        let mut successors: Vec<Vec<usize>> = Vec::with_capacity(cells * DIRECTIONS.len());
+        for point in grid.iter_points() {
+            for dir in DIRECTIONS {
+                successors.push(
+                    grid.get(point)
+                        .unwrap()
+                        .deflect(dir)
+                        .iter()
+                        .filter_map(|&next_dir| {
+                            let next = point.add_checked(next_dir, &grid_dimensions)?;
+                            Some(state(next, next_dir))
+                        })
+                        .collect(),
+                );
             }
-            Cell::SplitterHorizontal => {
-                if dir == EAST || dir == WEST {
-                    queue_next(&mut seen, &mut to_search, current, dir, &grid_dimensions);
-                } else {
-                    queue_next(&mut seen, &mut to_search, current, EAST, &grid_dimensions);
-                    queue_next(&mut seen, &mut to_search, current, WEST, &grid_dimensions);
+        }
+
+        let (component, count) = strongly_connected_components(&successors);
+        let mut members: Vec<Vec<usize>> = vec![Vec::new(); count];
+        for (state, &id) in component.iter().enumerate() {
+            members[id].push(state);
+        }
+        let mut graph = BeamGraph {
+            cols,
+            cells,
+            component,
+            reach: Vec::with_capacity(count),
+        };
+        // Components are numbered so that every edge leaving one points to a lower number, so
+        // everything a component leads to is already memoized when it is reached.
+        for states in members {
+            let reach = match states[..] {
+                [state] if successors[state].len() <= 1 && !successors[state].contains(&state) => {
+                    Reach::Chain {
+                        cell: state / DIRECTIONS.len(),
+                        next: successors[state].first().map(|&next| graph.component[next]),
+                    }
+                }
+                _ => {
+                    let mut bits = vec![0; cells.div_ceil(64)];
+                    for &state in &states {
+                        set_bit(&mut bits, state / DIRECTIONS.len());
+                        for &next in &successors[state] {
+                            let next = graph.component[next];
+                            if next != graph.component[state] {
+                                for (bit, other) in bits.iter_mut().zip(graph.resolve(Some(next))) {
+                                    *bit |= other;
+                                }
+                            }
+                        }
+                    }
+                    Reach::Cells(bits)
+                }
+            };
+            graph.reach.push(reach);
+        }
+        graph
+    }
+
+    /// The cells energized from `component`, as a bitset.
+    fn resolve(&self, mut component: Option<usize>) -> Vec<u64> {
+        let mut chain = Vec::new();
+        let mut bits = loop {
+            match component.map(|id| &self.reach[id]) {
+                None => break vec![0; self.cells.div_ceil(64)],
+                Some(Reach::Cells(bits)) => break bits.clone(),
+                Some(&Reach::Chain { cell, next }) => {
+                    chain.push(cell);
+                    component = next;
                 }
             }
-            Cell::SplitterVertical => {
-                if dir == NORTH || dir == SOUTH {
-                    queue_next(&mut seen, &mut to_search, current, dir, &grid_dimensions);
-                } else {
-                    queue_next(&mut seen, &mut to_search, current, NORTH, &grid_dimensions);
-                    queue_next(&mut seen, &mut to_search, current, SOUTH, &grid_dimensions);
+        };
+        for cell in chain {
+            set_bit(&mut bits, cell);
+        }
+        bits
+    }
+
+    /// The number of cells energized by a beam entering `start` heading in `start_dir`; the same as
+    /// `energized(&simulate(grid, start, start_dir))`.
+    pub fn energized(&self, start: GridPoint<usize>, start_dir: GridPointDelta<isize>) -> usize {
+        let state =
+            (start.row() * self.cols + start.col()) * DIRECTIONS.len() + direction_index(start_dir);
+        self.resolve(Some(self.component[state]))
+            .iter()
+            .map(|bits| bits.count_ones() as usize)
+            .sum()
+    }
+}
+
+fn set_bit(bits: &mut [u64], idx: usize) {
+    bits[idx / 64] |= 1 << (idx % 64);
+}
+
+/// Tarjan's algorithm, iteratively. Returns each node's component and the number of components,
+/// numbered in reverse topological order.
+fn strongly_connected_components(successors: &[Vec<usize>]) -> (Vec<usize>, usize) {
+    const UNVISITED: usize = usize::MAX;
+    let mut index = vec![UNVISITED; successors.len()];
+    let mut low = vec![0; successors.len()];
+    let mut on_stack = vec![false; successors.len()];
+    let mut component = vec![UNVISITED; successors.len()];
+    let mut stack = Vec::new();
+    let mut next_index = 0;
+    let mut count = 0;
+    for root in 0..successors.len() {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        index[root] = next_index;
+        low[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        let mut calls = vec![(root, 0)];
+        while let Some(&(node, edge)) = calls.last() {
+            if let Some(&next) = successors[node].get(edge) {
+                calls.last_mut().unwrap().1 += 1;
+                if index[next] == UNVISITED {
+                    index[next] = next_index;
+                    low[next] = next_index;
+                    next_index += 1;
+                    stack.push(next);
+                    on_stack[next] = true;
+                    calls.push((next, 0));
+                } else if on_stack[next] {
+                    low[node] = low[node].min(index[next]);
                 }
+                continue;
             }
-            Cell::MirrorNE => {
-                let new_dir = match dir {
-                    NORTH => EAST,
-                    EAST => NORTH,
-                    SOUTH => WEST,
-                    WEST => SOUTH,
-                    _ => panic!("unrecognized direction"),
-                };
-                queue_next(
-                    &mut seen,
-                    &mut to_search,
-                    current,
-                    new_dir,
-                    &grid_dimensions,
-                );
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low[parent] = low[parent].min(low[node]);
             }
-            Cell::MirrorNW => {
-                let new_dir = match dir {
-                    NORTH => WEST,
-                    WEST => NORTH,
-                    SOUTH => EAST,
-                    EAST => SOUTH,
-                    _ => panic!("unrecognized direction"),
-                };
-                queue_next(
-                    &mut seen,
-                    &mut to_search,
-                    current,
-                    new_dir,
-                    &grid_dimensions,
-                );
+            if low[node] == index[node] {
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component[member] = count;
+                    if member == node {
+                        break;
+                    }
+                }
+                count += 1;
             }
         }
     }
-    seen.into_iter()
-        .map(|(a, _)| a)
-        .collect::<HashSet<GridPoint<usize>>>()
-        .len()
+    (component, count)
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> usize {
-    let grid = parse(input);
-    simulate(&grid, GridPoint::new(0, 0), EAST)
-}
-
-#[allow(dead_code)]
-pub fn part2(input: &str) -> usize {
-    let grid = parse(input);
+/// Every beam entering the grid from an edge, heading inwards.
+fn edge_starts(grid: &Contraption) -> Vec<(GridPoint<usize>, GridPointDelta<isize>)> {
+    let Some(far) = far_corner(grid) else {
+        return Vec::new();
+    };
     let grid_dimensions = grid.dimensions();
     GridPoint::new(0, 0)
         .traverse_by(EAST, grid_dimensions)
@@ -129,98 +458,145 @@ pub fn part2(input: &str) -> usize {
                 .map(|start| (start, EAST)),
         )
         .chain(
-            GridPoint::new(grid.rows() - 1, 0)
+            GridPoint::new(far.row(), 0)
                 .traverse_by(EAST, grid_dimensions)
                 .map(|start| (start, NORTH)),
         )
         .chain(
-            GridPoint::new(0, grid.cols() - 1)
+            GridPoint::new(0, far.col())
                 .traverse_by(SOUTH, grid_dimensions)
                 .map(|start| (start, WEST)),
         )
-        .map(|(start, start_dir)| simulate(&grid, start, start_dir))
+        .collect()
+}
+
+pub fn part1(input: &str) -> Result<usize, InputError> {
+    let grid = parse(input)?;
+    Ok(energized(&simulate(&grid, GridPoint::new(0, 0), EAST)))
+}
+
+pub fn part2(input: &str) -> Result<usize, InputError> {
+    let grid = parse(input)?;
+    let graph = BeamGraph::new(&grid);
+    Ok(edge_starts(&grid)
+        .into_iter()
+        .map(|(start, start_dir)| graph.energized(start, start_dir))
         .max()
-        .unwrap()
+        .unwrap_or(0))
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    fn name(&self) -> &'static str {
+        "The Floor Will Be Lava"
+    }
+
+    fn day(&self) -> u32 {
+        16
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
+    }
+
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
+    }
 }
 
 #[allow(dead_code)]
 #[cfg(test)]
 mod tests {
     use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const EXAMPLE: &str = ".|...\\....
-|.-.\\.....
-.....|-...
-........|.
-..........
-.........\\
-..../.\\\\..
-.-.-/..|..
-.|....-|.\\
-..//.|....
-";
-    const DAY: Day = Day::Day16;
+    use crate::test_rng::Lcg;
 
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 46);
-    }
+    const EXAMPLE: &str = include_str!("../examples/16/example.in");
 
     #[test]
-    fn part2_example() {
-        assert_eq!(part2(EXAMPLE), 51);
+    fn energization_map() {
+        let grid = parse(EXAMPLE).unwrap();
+        let energy = simulate(&grid, GridPoint::new(0, 0), EAST);
+        assert_eq!(energized(&energy), 46);
+        assert_eq!(
+            *energy.get(GridPoint::new(0, 0)).unwrap(),
+            direction_bit(EAST)
+        );
+        // The `|` at (0, 1) turns the beam south, so the cell below it is entered only heading south.
+        assert_eq!(
+            *energy.get(GridPoint::new(1, 1)).unwrap(),
+            direction_bit(SOUTH)
+        );
+        assert_eq!(*energy.get(GridPoint::new(9, 9)).unwrap(), 0);
     }
 
     #[test]
-    fn part1_test() {
+    fn custom_elements() {
+        let mut elements = Elements::standard();
+        elements.register('#', &Absorber);
+        elements.register('>', &Gate { dir: EAST });
+        elements.register('@', &Prism { clockwise: true });
+        let grid = parse_with(".>.#\n@...\n", &elements).unwrap();
+        let from = |row, col, dir| energized(&simulate(&grid, GridPoint::new(row, col), dir));
+        assert_eq!(from(0, 0, EAST), 4);
+        assert_eq!(from(0, 2, WEST), 2);
+        assert_eq!(from(0, 3, WEST), 1);
+        // The prism turns eastward beams south, out of the grid, and westward ones north.
+        assert_eq!(from(1, 0, EAST), 1);
+        assert_eq!(from(1, 3, WEST), 5);
         assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            7242
+            BeamGraph::new(&grid).energized(GridPoint::new(0, 0), EAST),
+            4
         );
+
+        let error = part1(".>.#\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 2));
+        elements.register('.', &Absorber);
+        let grid = parse_with("..\n", &elements).unwrap();
+        assert_eq!(energized(&simulate(&grid, GridPoint::new(0, 0), EAST)), 1);
     }
 
     #[test]
-    fn part2_test() {
-        assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            7572
-        );
+    fn empty_contraption() {
+        for input in ["", "\n"] {
+            let error = part1(input).unwrap_err();
+            assert_eq!((error.line, error.column), (1, 1));
+            assert!(part2(input).is_err());
+        }
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
-    }
-
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    #[test]
+    fn memoized_matches_simulation() {
+        // The example plus pseudo-random grids dense enough in splitters to form loops.
+        let mut rng = Lcg::new(16);
+        let mut inputs = vec![EXAMPLE.to_string()];
+        for _ in 0..20 {
+            let mut input = String::new();
+            for _ in 0..8 {
+                for _ in 0..9 {
+                    input.push(".....|-/\\".as_bytes()[rng.below(9) as usize] as char);
+                }
+                input.push('\n');
+            }
+            inputs.push(input);
+        }
+        for input in inputs {
+            let grid = parse(&input).unwrap();
+            let graph = BeamGraph::new(&grid);
+            for (start, start_dir) in edge_starts(&grid) {
+                assert_eq!(
+                    graph.energized(start, start_dir),
+                    energized(&simulate(&grid, start, start_dir)),
+                    "entering {:?} heading {:?} in\n{}",
+                    start,
+                    start_dir,
+                    input
+                );
+            }
+        }
     }
 }
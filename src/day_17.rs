@@ -1,219 +1,328 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
 use advent_of_code::{
-    grid::{Grid, GridPoint, GridPointDelta, EAST, NORTH, SOUTH, WEST},
+    grid::{Grid, GridPoint, EAST, NORTH, SOUTH},
     parse::{parsers, Parser},
 };
 
-use std::collections::{BinaryHeap, HashSet};
+use crate::{
+    input::InputError,
+    pathfinding::{find_path, MovementRules, Path},
+    solution::{Answer, Solution},
+};
+
+const EXPECTED: &str = "a digit or `#`";
+
+/// Blocks the crucible cannot enter, written as `#` or `0`.
+const WALL: u32 = 0;
 
-fn parse(input: &str) -> Grid<u32> {
-    parsers::chars(|c| c.is_numeric())
-        .map(|c| c.to_digit(10).unwrap())
+fn is_block(c: char) -> bool {
+    c.is_ascii_digit() || c == '#'
+}
+
+fn parse(input: &str) -> Result<Grid<u32>, InputError> {
+    let rows: Vec<Vec<u32>> = parsers::chars(is_block)
+        .map(|c| c.to_digit(10).unwrap_or(WALL))
         .many()
         .map(|i| i.collect())
         .many_lines("\n")
-        .map(|i| Grid::of_vec_of_vecs(i.collect()).unwrap())
+        .map(|i| i.collect())
         .parse(input)
         .finish()
-        .expect("Failed to parse input")
+        .map_err(|_| InputError::in_grid(input, EXPECTED, is_block))?;
+    Grid::of_vec_of_vecs(rows).map_err(|_| InputError::in_grid(input, EXPECTED, is_block))
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct SearchElement {
-    grid_point: GridPoint<usize>,
-    next_dir: GridPointDelta<isize>,
-    weight: u32,
-    cost: u32,
+/// The crucible moves between `min_move` and `max_move` blocks in a straight line and must then
+/// turn left or right.
+struct Crucible {
+    min_move: usize,
+    max_move: usize,
 }
 
-impl SearchElement {
-    fn new(
-        grid_point: GridPoint<usize>,
-        next_dir: GridPointDelta<isize>,
-        target: GridPoint<usize>,
-        cost: u32,
-    ) -> Self {
-        SearchElement {
-            grid_point,
-            next_dir,
-            weight: cost + (target.sub::<isize>(grid_point).unwrap().l1_norm()) as u32,
-            cost,
-        }
+impl MovementRules for Crucible {
+    fn min_segment(&self) -> usize {
+        self.min_move
     }
 
-    fn unwrap(self) -> (GridPoint<usize>, GridPointDelta<isize>, u32) {
-        (self.grid_point, self.next_dir, self.cost)
+    fn max_segment(&self) -> usize {
+        self.max_move
     }
-}
 
-impl PartialOrd for SearchElement {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(&other))
+    /// Every block that can be entered loses at least 1 heat.
+    fn estimate(&self, from: GridPoint<usize>, to: GridPoint<usize>) -> u32 {
+        (from.row().abs_diff(to.row()) + from.col().abs_diff(to.col())) as u32
     }
 }
 
-impl Ord for SearchElement {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.weight.cmp(&self.weight)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchError {
+    /// The start or target is outside the grid.
+    OutOfBounds(GridPoint<usize>),
+    /// The start or target is a wall.
+    Blocked(GridPoint<usize>),
+    /// The move limits allow no segment at all.
+    InvalidMoves {
+        min_move: usize,
+        max_move: usize,
+    },
+    Unreachable,
+}
+
+impl Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds(point) => {
+                write!(f, "({}, {}) is outside the grid", point.row(), point.col())
+            }
+            Self::Blocked(point) => write!(f, "({}, {}) is a wall", point.row(), point.col()),
+            Self::InvalidMoves { min_move, max_move } => write!(
+                f,
+                "cannot move between {} and {} blocks at a time",
+                min_move, max_move
+            ),
+            Self::Unreachable => write!(f, "the target cannot be reached"),
+        }
     }
 }
 
-fn next_dirs(dir: GridPointDelta<isize>) -> (GridPointDelta<isize>, GridPointDelta<isize>) {
-    match dir {
-        NORTH | SOUTH => (EAST, WEST),
-        EAST | WEST => (NORTH, SOUTH),
-        _ => panic!("unrecognized direction"),
+impl Error for SearchError {}
+
+pub type CruciblePath = Path;
+
+/// Draws the path over the grid the way the puzzle does, with `>`, `v`, `<` and `^` marking the
+/// direction the crucible entered each block in.
+#[allow(dead_code)]
+pub fn render(path: &CruciblePath, grid: &Grid<u32>) -> String {
+    let mut rows: Vec<Vec<char>> = grid
+        .all_rows()
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|loss| match loss {
+                    WALL => '#',
+                    loss => char::from_digit(loss, 10).unwrap_or('?'),
+                })
+                .collect()
+        })
+        .collect();
+    for segment in &path.segments {
+        let arrow = match segment.dir {
+            NORTH => '^',
+            SOUTH => 'v',
+            EAST => '>',
+            _ => '<',
+        };
+        let mut point = segment.start;
+        while point != segment.end {
+            point = (point + segment.dir).unwrap();
+            rows[point.row()][point.col()] = arrow;
+        }
     }
+    rows.into_iter()
+        .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+        .collect()
 }
 
-fn search(
+/// The cheapest route from `start` to `target`.
+pub fn search_path(
     grid: &Grid<u32>,
     start: GridPoint<usize>,
-    starting_dirs: Vec<GridPointDelta<isize>>,
     target: GridPoint<usize>,
     min_move: usize,
     max_move: usize,
-) -> u32 {
-    let mut to_search: BinaryHeap<SearchElement> = BinaryHeap::new();
-    let mut seen: HashSet<(GridPoint<usize>, GridPointDelta<isize>)> = HashSet::new();
-    starting_dirs.into_iter().for_each(|starting_dir| {
-        to_search.push(SearchElement::new(start, starting_dir, target, 0));
-    });
-    let grid_dimensions = grid.dimensions();
-    while let Some(next) = to_search.pop() {
-        let (current, dir, mut cost) = next.unwrap();
-        if seen.contains(&(current, dir)) {
-            continue;
-        }
-        seen.insert((current, dir));
-        if current == target {
-            return cost;
-        }
-        let (left, right) = next_dirs(dir);
-        for (idx, point) in current
-            .traverse_by(dir, grid_dimensions)
-            .skip(1)
-            .take(max_move)
-            .enumerate()
-        {
-            cost += grid.get(point).unwrap();
-            if idx + 1 >= min_move {
-                to_search.push(SearchElement::new(point, left, target, cost));
-                to_search.push(SearchElement::new(point, right, target, cost));
-            }
+) -> Result<CruciblePath, SearchError> {
+    if min_move == 0 || min_move > max_move {
+        return Err(SearchError::InvalidMoves { min_move, max_move });
+    }
+    for point in [start, target] {
+        match grid.get(point) {
+            Err(_) => return Err(SearchError::OutOfBounds(point)),
+            Ok(&WALL) => return Err(SearchError::Blocked(point)),
+            Ok(_) => {}
         }
     }
-    panic!("Terminated without reaching target");
+    let crucible = Crucible { min_move, max_move };
+    let heat_loss = |_, &loss: &u32| (loss != WALL).then_some(loss);
+    find_path(grid, &crucible, heat_loss, start, target).ok_or(SearchError::Unreachable)
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> u32 {
-    let grid = parse(input);
-
-    search(
-        &grid,
-        GridPoint::new(0, 0),
-        vec![EAST, SOUTH],
-        GridPoint::new(grid.rows() - 1, grid.cols() - 1),
-        1,
-        3,
-    )
+pub fn search(
+    grid: &Grid<u32>,
+    start: GridPoint<usize>,
+    target: GridPoint<usize>,
+    min_move: usize,
+    max_move: usize,
+) -> Result<u32, SearchError> {
+    search_path(grid, start, target, min_move, max_move).map(|path| path.cost)
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> u32 {
-    let grid = parse(input);
-
-    search(
-        &grid,
-        GridPoint::new(0, 0),
-        vec![EAST, SOUTH],
-        GridPoint::new(grid.rows() - 1, grid.cols() - 1),
-        4,
-        10,
-    )
+/// The bottom-right block, or `OutOfBounds` if the grid has no blocks at all.
+pub fn bottom_right(grid: &Grid<u32>) -> Result<GridPoint<usize>, SearchError> {
+    match (grid.rows().checked_sub(1), grid.cols().checked_sub(1)) {
+        (Some(row), Some(col)) => Ok(GridPoint::new(row, col)),
+        _ => Err(SearchError::OutOfBounds(GridPoint::new(0, 0))),
+    }
+}
+
+/// Solves the puzzle's route from the top-left to the bottom-right block, blaming the whole grid if
+/// walls make that impossible.
+fn corner_to_corner(input: &str, min_move: usize, max_move: usize) -> Result<u32, InputError> {
+    let grid = parse(input)?;
+    bottom_right(&grid)
+        .and_then(|target| search(&grid, GridPoint::new(0, 0), target, min_move, max_move))
+        .map_err(|err| {
+            InputError::at(
+                input,
+                1,
+                1,
+                &format!("a grid with a route from corner to corner ({})", err),
+            )
+        })
+}
+
+pub fn part1(input: &str) -> Result<u32, InputError> {
+    corner_to_corner(input, 1, 3)
+}
+
+pub fn part2(input: &str) -> Result<u32, InputError> {
+    corner_to_corner(input, 4, 10)
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    fn name(&self) -> &'static str {
+        "Clumsy Crucible"
+    }
+
+    fn day(&self) -> u32 {
+        17
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
+    }
+
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
+    }
 }
 
 #[allow(dead_code)]
 #[cfg(test)]
 mod tests {
     use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const EXAMPLE: &str = "2413432311323
-3215453535623
-3255245654254
-3446585845452
-4546657867536
-1438598798454
-4457876987766
-3637877979653
-4654967986887
-4564679986453
-1224686865563
-2546548887735
-4322674655533
-";
-    const DAY: Day = Day::Day17;
 
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 102);
+    const EXAMPLE: &str = include_str!("../examples/17/example.in");
+
+    fn example_path(min_move: usize, max_move: usize) -> (Grid<u32>, CruciblePath) {
+        let grid = parse(EXAMPLE).unwrap();
+        let target = bottom_right(&grid).unwrap();
+        let path = search_path(&grid, GridPoint::new(0, 0), target, min_move, max_move).unwrap();
+        (grid, path)
     }
 
     #[test]
-    fn part2_example() {
-        assert_eq!(part2(EXAMPLE), 94);
+    fn path_matches_cost() {
+        for (min_move, max_move, cost) in [(1, 3, 102), (4, 10, 94)] {
+            let (grid, path) = example_path(min_move, max_move);
+            assert_eq!(path.cost, cost);
+            assert_eq!(
+                path.segments
+                    .iter()
+                    .map(|segment| segment.cost)
+                    .sum::<u32>(),
+                cost
+            );
+            let points = path.points();
+            assert_eq!(points.first(), Some(&GridPoint::new(0, 0)));
+            assert_eq!(points.last(), Some(&bottom_right(&grid).unwrap()));
+            assert_eq!(
+                points[1..]
+                    .iter()
+                    .map(|&point| grid.get(point).unwrap())
+                    .sum::<u32>(),
+                cost
+            );
+            for pair in path.segments.windows(2) {
+                assert_eq!(pair[0].end, pair[1].start);
+                assert_ne!(pair[0].dir, pair[1].dir);
+                assert_ne!(pair[0].dir, -pair[1].dir);
+            }
+            for segment in &path.segments {
+                assert!((min_move..=max_move).contains(&segment.length()));
+            }
+            assert_eq!(path.turn_points().len(), path.segments.len() + 1);
+        }
     }
 
     #[test]
-    fn part1_test() {
+    fn renders_path() {
+        let (grid, path) = example_path(1, 3);
+        let rendered = render(&path, &grid);
+        assert!(rendered.starts_with('2'));
         assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            767
+            rendered.chars().filter(|c| "<>^v".contains(*c)).count(),
+            path.points().len() - 1
         );
+        assert_eq!(rendered.lines().count(), grid.rows());
     }
 
     #[test]
-    fn part2_test() {
+    fn walls_and_endpoints() {
+        let grid = parse(
+            "1111
+##1#
+1111
+1#00
+",
+        )
+        .unwrap();
+        let path = search_path(&grid, GridPoint::new(0, 0), GridPoint::new(2, 0), 1, 3).unwrap();
+        assert_eq!(path.cost, 6);
+        assert_eq!(
+            search(&grid, GridPoint::new(0, 3), GridPoint::new(0, 3), 1, 3),
+            Ok(0)
+        );
+        assert_eq!(
+            search(&grid, GridPoint::new(0, 0), GridPoint::new(3, 0), 2, 3),
+            Err(SearchError::Unreachable)
+        );
+        assert_eq!(
+            search(&grid, GridPoint::new(0, 0), GridPoint::new(3, 2), 1, 3),
+            Err(SearchError::Blocked(GridPoint::new(3, 2)))
+        );
+        assert_eq!(
+            search(&grid, GridPoint::new(4, 0), GridPoint::new(0, 0), 1, 3),
+            Err(SearchError::OutOfBounds(GridPoint::new(4, 0)))
+        );
         assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            904
+            search(&grid, GridPoint::new(0, 0), GridPoint::new(2, 0), 4, 3),
+            Err(SearchError::InvalidMoves {
+                min_move: 4,
+                max_move: 3
+            })
         );
+        let error = part1("11\n#0\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+        assert!(error.expected.contains("is a wall"));
+        assert!(part1("").is_err());
+        assert!(part2("\n").is_err());
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
-    }
-
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    #[test]
+    fn malformed_grid() {
+        let error = part1("123\n1x3\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        let error = part1("123\n12\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
     }
 }
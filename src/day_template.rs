@@ -1,92 +1,47 @@
 use advent_of_code::parse::{parsers, Parser};
 
-fn parse(input: &str) -> String {
+use crate::{
+    input::InputError,
+    solution::{Answer, Solution},
+};
+
+fn parse(input: &str) -> Result<String, InputError> {
     parsers::any()
         .parse(input)
         .finish()
-        .expect("Failed to parse input")
+        .map_err(|_| InputError::end_of_input(input, "anything"))
 }
 
-#[allow(dead_code)]
-pub fn part1(input: &str) -> u32 {
-    parse(input);
-    0
+pub fn part1(input: &str) -> Result<u32, InputError> {
+    parse(input)?;
+    Ok(0)
 }
 
-#[allow(dead_code)]
-pub fn part2(input: &str) -> u32 {
-    parse(input);
-    0
+pub fn part2(input: &str) -> Result<u32, InputError> {
+    parse(input)?;
+    Ok(0)
 }
 
-#[allow(dead_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
-    extern crate test;
-    use advent_of_code::{day::Day, web_api::load_question_input};
-    use test::Bencher;
-
-    const EXAMPLE: &str = "";
-    const DAY: Day = Day::Day01;
-
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(EXAMPLE), 0);
-    }
+pub struct Day01;
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(EXAMPLE), 0);
+impl Solution for Day01 {
+    fn name(&self) -> &'static str {
+        ""
     }
 
-    #[test]
-    fn part1_test() {
-        assert_eq!(
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            0
-        );
+    fn day(&self) -> u32 {
+        1
     }
 
-    #[test]
-    fn part2_test() {
-        assert_eq!(
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY
-            )),
-            0
-        );
+    fn part1(&self, input: &str) -> Result<Answer, InputError> {
+        part1(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part1_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part1(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn part2(&self, input: &str) -> Result<Answer, InputError> {
+        part2(input).map(Answer::from)
     }
 
-    #[bench]
-    fn part2_bench(b: &mut Bencher) {
-        b.iter(|| {
-            part2(&load_question_input(
-                crate::YEAR,
-                crate::COOKIE_PATH,
-                crate::INPUT_CACHE,
-                DAY,
-            ))
-        });
+    fn parse(&self, _part: u32, input: &str) -> Option<Result<(), InputError>> {
+        Some(parse(input).map(drop))
     }
 }
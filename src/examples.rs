@@ -0,0 +1,136 @@
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+use crate::{ledger, solution::Solution};
+
+/// Examples for day N live in `examples/NN/NAME.in`, with their expected answers in
+/// `examples/NN/answers.toml` under a `[NAME]` table.
+pub const EXAMPLES_DIR: &str = "examples";
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+pub fn example_dir(day: u32) -> PathBuf {
+    PathBuf::from(EXAMPLES_DIR).join(format!("{:02}", day))
+}
+
+pub fn example_path(day: u32, name: &str) -> PathBuf {
+    example_dir(day).join(format!("{}.in", name))
+}
+
+/// Reads an example by name. Only plain names are looked up, so a name cannot reach outside
+/// `examples/NN`.
+pub fn load_example(day: u32, name: &str) -> Result<String, String> {
+    if !is_example_name(name) {
+        return Err(format!(
+            "example `{}` missing for day {}: names are letters, digits, `_` and `-`",
+            name, day
+        ));
+    }
+    let path = example_path(day, name);
+    fs::read_to_string(&path).map_err(|err| {
+        format!(
+            "example `{}` missing for day {}: could not read {}: {}",
+            name,
+            day,
+            path.display(),
+            err
+        )
+    })
+}
+
+fn is_example_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// The expected answers for a day's examples, keyed by example name and part. A day without an
+/// answers file has no expectations.
+pub fn expected_answers(day: u32) -> Result<BTreeMap<(String, u32), String>, String> {
+    let path = example_dir(day).join(ANSWERS_FILE);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(err) => return Err(format!("failed to read {}: {}", path.display(), err)),
+    };
+    ledger::parse_answer_tables(&contents, "an `[example_name]` header", |name| {
+        is_example_name(name).then(|| name.to_string())
+    })
+    .map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+/// Runs every example of `solution` that has an expected answer and describes each mismatch.
+pub fn check_examples(solution: &dyn Solution) -> Result<Vec<String>, String> {
+    let day = solution.day();
+    let mut failures = Vec::new();
+    for ((name, part), expected) in expected_answers(day)? {
+        let input = load_example(day, &name)?;
+        match solution.part(part, &input).unwrap() {
+            Ok(answer) if answer.to_string() == expected => {}
+            Ok(answer) => failures.push(format!(
+                "day {} example `{}` part {}: expected {}, got {}",
+                day, name, part, expected, answer
+            )),
+            Err(err) => failures.push(format!(
+                "day {} example `{}` part {}: {}",
+                day, name, part, err
+            )),
+        }
+    }
+    Ok(failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{self, SOLUTIONS};
+
+    #[test]
+    fn day_10_examples() {
+        let answers = expected_answers(10).unwrap();
+        assert_eq!(
+            answers.get(&("part2_b".to_string(), 2)),
+            Some(&"8".to_string())
+        );
+        assert!(load_example(10, "part2_b")
+            .unwrap()
+            .starts_with(".F----7F7F7F7F-7"));
+        assert!(load_example(10, "no_such_example")
+            .unwrap_err()
+            .starts_with("example `no_such_example` missing for day 10"));
+    }
+
+    #[test]
+    fn rejects_bad_example_names() {
+        assert!(ledger::parse_answer_tables("[../day]\n", "a name", |name| {
+            is_example_name(name).then(|| name.to_string())
+        })
+        .is_err());
+        assert!(load_example(10, "../../answers")
+            .unwrap_err()
+            .starts_with("example `../../answers` missing for day 10"));
+    }
+
+    /// Every example with a recorded answer, for every registered day.
+    #[test]
+    fn examples_match_expected_answers() {
+        let failures: Vec<String> = SOLUTIONS
+            .iter()
+            .flat_map(|solution| check_examples(*solution).unwrap())
+            .collect();
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn example_dirs_are_registered() {
+        for entry in fs::read_dir(EXAMPLES_DIR).unwrap() {
+            let name = entry.unwrap().file_name().into_string().unwrap();
+            let day: u32 = name.parse().unwrap();
+            assert!(
+                solution::find(day).is_some(),
+                "{}/{} has no registered solution",
+                EXAMPLES_DIR,
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use advent_of_code::parse::Parser;
+
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    iter::{Enumerate, Peekable},
+    str::Lines,
+};
+
+const SNIPPET_LEN: usize = 24;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputError {
+    pub line: usize,
+    pub column: usize,
+    pub snippet: String,
+    pub expected: String,
+}
+
+impl InputError {
+    /// `line` and `column` are 1-based. The snippet is taken from `input` starting at that position.
+    pub fn at(input: &str, line: usize, column: usize, expected: &str) -> Self {
+        InputError {
+            line,
+            column,
+            snippet: input
+                .lines()
+                .nth(line - 1)
+                .unwrap_or("")
+                .chars()
+                .skip(column - 1)
+                .take(SNIPPET_LEN)
+                .collect(),
+            expected: expected.to_string(),
+        }
+    }
+
+    pub fn end_of_input(input: &str, expected: &str) -> Self {
+        Self::at(input, input.lines().count() + 1, 1, expected)
+    }
+
+    /// Locates the first character of `input` rejected by `valid`, for formats where any other
+    /// character is the only way to go wrong.
+    pub fn invalid_char(input: &str, expected: &str, valid: impl Fn(char) -> bool) -> Self {
+        input
+            .lines()
+            .enumerate()
+            .find_map(|(line_idx, line)| {
+                line.chars()
+                    .position(|c| !valid(c))
+                    .map(|column_idx| Self::at(input, line_idx + 1, column_idx + 1, expected))
+            })
+            .unwrap_or_else(|| Self::end_of_input(input, expected))
+    }
+
+    /// Locates the problem in a character grid (or several grids separated by blank lines): either the
+    /// first character rejected by `valid` or the first row whose length differs from the row above it.
+    pub fn in_grid(input: &str, expected: &str, valid: impl Fn(char) -> bool) -> Self {
+        let mut row_len: Option<usize> = None;
+        for (line_idx, line) in input.lines().enumerate() {
+            if let Some(column_idx) = line.chars().position(|c| !valid(c)) {
+                return Self::at(input, line_idx + 1, column_idx + 1, expected);
+            }
+            let len = line.chars().count();
+            match row_len {
+                _ if len == 0 => row_len = None,
+                Some(row_len) if len != row_len => {
+                    return Self::at(
+                        input,
+                        line_idx + 1,
+                        usize::min(len, row_len) + 1,
+                        &format!("a row of length {}", row_len),
+                    );
+                }
+                _ => row_len = Some(len),
+            }
+        }
+        Self::end_of_input(input, expected)
+    }
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found ",
+            self.line, self.column, self.expected
+        )?;
+        if self.snippet.is_empty() {
+            write!(f, "end of line")
+        } else {
+            write!(f, "`{}`", self.snippet)
+        }
+    }
+}
+
+impl Error for InputError {}
+
+/// A piece of a line that remembers the column it starts at, so that a line taken apart piece by
+/// piece can blame the exact column of the piece it rejects. Line parsers return that column as
+/// their error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field<'a> {
+    text: &'a str,
+    column: usize,
+}
+
+impl<'a> Field<'a> {
+    pub fn new(line: &'a str) -> Self {
+        Field {
+            text: line,
+            column: 1,
+        }
+    }
+
+    pub fn text(self) -> &'a str {
+        self.text
+    }
+
+    /// The 1-based column of the first character.
+    pub fn column(self) -> usize {
+        self.column
+    }
+
+    /// The column of the character at byte offset `offset`.
+    fn column_at(self, offset: usize) -> usize {
+        self.column + self.text[..offset].chars().count()
+    }
+
+    /// The column just past the last character.
+    pub fn end(self) -> usize {
+        self.column_at(self.text.len())
+    }
+
+    fn slice(self, from: usize, to: usize) -> Self {
+        Field {
+            text: &self.text[from..to],
+            column: self.column_at(from),
+        }
+    }
+
+    /// What follows `prefix`, or the column of the first character that differs from it.
+    pub fn tag(self, prefix: &str) -> Result<Self, usize> {
+        if self.text.starts_with(prefix) {
+            Ok(self.slice(prefix.len(), self.text.len()))
+        } else {
+            Err(self.column
+                + self
+                    .text
+                    .chars()
+                    .zip(prefix.chars())
+                    .take_while(|(a, b)| a == b)
+                    .count())
+        }
+    }
+
+    /// The pieces before and after the first `separator`, or the end of the field if there is none.
+    pub fn split_once(self, separator: &str) -> Result<(Self, Self), usize> {
+        let idx = self.text.find(separator).ok_or(self.end())?;
+        Ok((
+            self.slice(0, idx),
+            self.slice(idx + separator.len(), self.text.len()),
+        ))
+    }
+
+    /// Every piece between `separator`s, including empty ones.
+    pub fn split(self, separator: &'a str) -> impl Iterator<Item = Self> + 'a {
+        let mut column = self.column;
+        self.text.split(separator).map(move |text| {
+            let field = Field { text, column };
+            column = field.end() + separator.chars().count();
+            field
+        })
+    }
+
+    /// Every non-empty piece between runs of spaces.
+    pub fn words(self) -> impl Iterator<Item = Self> + 'a {
+        self.split(" ").filter(|field| !field.text.is_empty())
+    }
+
+    /// The field without its leading spaces.
+    pub fn trim_start(self) -> Self {
+        let trimmed = self.text.trim_start_matches(' ');
+        self.slice(self.text.len() - trimmed.len(), self.text.len())
+    }
+
+    /// Nothing, or the column of the first character if the field is not empty.
+    pub fn empty(self) -> Result<(), usize> {
+        if self.text.is_empty() {
+            Ok(())
+        } else {
+            Err(self.column)
+        }
+    }
+
+    /// Parses the whole field, blaming its first column if `parser` rejects it.
+    pub fn parse<P: Parser>(self, parser: P) -> Result<P::Output, usize> {
+        parser.parse(self.text).finish().map_err(|_| self.column)
+    }
+
+    /// Maps every character with `parse`, or returns the column of the first one it rejects.
+    pub fn chars<T>(self, parse: impl Fn(char) -> Option<T>) -> Result<Vec<T>, usize> {
+        self.text
+            .chars()
+            .enumerate()
+            .map(|(idx, c)| parse(c).ok_or(self.column + idx))
+            .collect()
+    }
+}
+
+/// Walks an input line by line so that each parsed line can be blamed by number when it is rejected.
+pub struct InputLines<'a> {
+    input: &'a str,
+    lines: Peekable<Enumerate<Lines<'a>>>,
+}
+
+impl<'a> InputLines<'a> {
+    pub fn new(input: &'a str) -> Self {
+        InputLines {
+            input,
+            lines: input.lines().enumerate().peekable(),
+        }
+    }
+
+    pub fn next_line<T>(
+        &mut self,
+        expected: &str,
+        parse: impl FnOnce(Field<'_>) -> Result<T, usize>,
+    ) -> Result<T, InputError> {
+        match self.lines.next() {
+            Some((idx, line)) => parse(Field::new(line))
+                .map_err(|column| InputError::at(self.input, idx + 1, column, expected)),
+            None => Err(InputError::end_of_input(self.input, expected)),
+        }
+    }
+
+    pub fn blank_line(&mut self) -> Result<(), InputError> {
+        self.next_line("an empty line", |line| line.empty())
+    }
+
+    /// Parses every line up to the next blank line (which is consumed) or the end of input.
+    pub fn until_blank<T>(
+        &mut self,
+        expected: &str,
+        mut parse: impl FnMut(Field<'_>) -> Result<T, usize>,
+    ) -> Result<Vec<T>, InputError> {
+        let mut parsed = Vec::new();
+        while let Some((idx, line)) = self.lines.next() {
+            if line.is_empty() {
+                break;
+            }
+            parsed.push(
+                parse(Field::new(line))
+                    .map_err(|column| InputError::at(self.input, idx + 1, column, expected))?,
+            );
+        }
+        Ok(parsed)
+    }
+
+    pub fn is_empty(&mut self) -> bool {
+        self.lines.peek().is_none()
+    }
+
+    /// Fails on any line left over, blaming it from its first column.
+    pub fn finish(mut self) -> Result<(), InputError> {
+        match self.lines.next() {
+            Some((idx, _)) => Err(InputError::at(self.input, idx + 1, 1, "the end of input")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses each line of `input` on its own, reporting the first line `parse` rejects.
+pub fn parse_lines<T>(
+    input: &str,
+    expected: &str,
+    parse: impl FnMut(Field<'_>) -> Result<T, usize>,
+) -> Result<Vec<T>, InputError> {
+    let mut lines = InputLines::new(input);
+    let parsed = lines.until_blank(expected, parse)?;
+    match lines.finish() {
+        Ok(()) => Ok(parsed),
+        // `until_blank` stopped early at a blank line, which is wrong from its first column.
+        Err(_) => Err(InputError::at(input, parsed.len() + 1, 1, expected)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code::parse::parsers;
+
+    #[test]
+    fn snippet_starts_at_column() {
+        let error = InputError::at("abc\ndefgh\n", 2, 3, "a digit");
+        assert_eq!(error.snippet, "fgh");
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: expected a digit, found `fgh`"
+        );
+    }
+
+    #[test]
+    fn grid_errors() {
+        assert_eq!(
+            InputError::in_grid("..#\n.x.\n", "`.` or `#`", |c| c == '.' || c == '#'),
+            InputError::at("..#\n.x.\n", 2, 2, "`.` or `#`")
+        );
+        let ragged = InputError::in_grid("...\n..\n\n.\n", "`.`", |c| c == '.');
+        assert_eq!((ragged.line, ragged.column), (2, 3));
+        assert_eq!(ragged.expected, "a row of length 3");
+    }
+
+    #[test]
+    fn parse_lines_reports_line_and_column() {
+        let parse = |line: Field<'_>| {
+            let (name, value) = line.tag("set ")?.split_once(" = ")?;
+            Ok((
+                name.text().to_string(),
+                value.parse(parsers::number::<u32>())?,
+            ))
+        };
+        assert_eq!(
+            parse_lines("set a = 1\nset b = 2\n", "an assignment", parse),
+            Ok(vec![("a".to_string(), 1), ("b".to_string(), 2)])
+        );
+        let error = parse_lines("set a = 1\nset b = two\n", "an assignment", parse).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 9));
+        assert_eq!(error.snippet, "two");
+        let error = parse_lines("set a = 1\nsex b = 2\n", "an assignment", parse).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        let error = parse_lines("set a = 1\nset b\n", "an assignment", parse).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 6));
+        let error = parse_lines("set a = 1\n\nset b = 2\n", "an assignment", parse).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+    }
+
+    #[test]
+    fn fields_keep_their_columns() {
+        let line = Field::new("Card  1:  4 12 | é 7");
+        let (card, numbers) = line.split_once(":").unwrap();
+        assert_eq!(card.tag("Card").unwrap().trim_start().column(), 7);
+        let (winning, have) = numbers.split_once(" | ").unwrap();
+        let columns = |field: Field<'_>| field.words().map(Field::column).collect::<Vec<_>>();
+        assert_eq!(columns(winning), vec![11, 13]);
+        assert_eq!(columns(have), vec![18, 20]);
+        assert_eq!(
+            have.parse(parsers::number::<u32>().list(" ")).err(),
+            Some(18)
+        );
+        assert_eq!(Field::new("#.x").chars(|c| (c != 'x').then_some(c)), Err(3));
+    }
+}
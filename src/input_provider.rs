@@ -0,0 +1,152 @@
+use std::{
+    env,
+    error::Error,
+    fmt::{self, Display},
+    fs, io,
+    path::PathBuf,
+};
+
+/// Overrides the directory the offline provider reads `N.in` files from.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+#[derive(Debug)]
+pub struct MissingInput {
+    pub day: u32,
+    pub path: PathBuf,
+    pub reason: io::Error,
+}
+
+impl Display for MissingInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "input missing for day {}: could not read {}: {}",
+            self.day,
+            self.path.display(),
+            self.reason
+        )
+    }
+}
+
+impl Error for MissingInput {}
+
+pub trait InputProvider {
+    fn load(&self, day: u32) -> Result<String, MissingInput>;
+}
+
+/// Reads puzzle inputs from a local directory and never touches the network.
+pub struct OfflineInput {
+    dir: PathBuf,
+}
+
+impl OfflineInput {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        OfflineInput { dir: dir.into() }
+    }
+
+    /// Uses `$AOC_INPUT_DIR` when set, otherwise the crate's `input` cache.
+    pub fn from_env() -> Self {
+        Self::new(env::var_os(INPUT_DIR_VAR).unwrap_or_else(|| crate::INPUT_CACHE.into()))
+    }
+
+    pub fn path(&self, day: u32) -> PathBuf {
+        self.dir.join(format!("{}.in", day))
+    }
+}
+
+impl InputProvider for OfflineInput {
+    fn load(&self, day: u32) -> Result<String, MissingInput> {
+        let path = self.path(day);
+        fs::read_to_string(&path).map_err(|reason| MissingInput { day, path, reason })
+    }
+}
+
+#[cfg(feature = "network")]
+pub use network::NetworkInput;
+
+#[cfg(feature = "network")]
+mod network {
+    use advent_of_code::{day::Day, web_api::load_question_input};
+
+    use super::{InputProvider, MissingInput, OfflineInput};
+
+    fn day_of_number(day: u32) -> Option<Day> {
+        match day {
+            1 => Some(Day::Day01),
+            2 => Some(Day::Day02),
+            3 => Some(Day::Day03),
+            4 => Some(Day::Day04),
+            5 => Some(Day::Day05),
+            6 => Some(Day::Day06),
+            7 => Some(Day::Day07),
+            8 => Some(Day::Day08),
+            9 => Some(Day::Day09),
+            10 => Some(Day::Day10),
+            11 => Some(Day::Day11),
+            12 => Some(Day::Day12),
+            13 => Some(Day::Day13),
+            14 => Some(Day::Day14),
+            15 => Some(Day::Day15),
+            16 => Some(Day::Day16),
+            17 => Some(Day::Day17),
+            18 => Some(Day::Day18),
+            19 => Some(Day::Day19),
+            20 => Some(Day::Day20),
+            21 => Some(Day::Day21),
+            22 => Some(Day::Day22),
+            23 => Some(Day::Day23),
+            24 => Some(Day::Day24),
+            25 => Some(Day::Day25),
+            _ => None,
+        }
+    }
+
+    /// Reads from the offline provider first and downloads missing days with the session cookie.
+    pub struct NetworkInput;
+
+    impl InputProvider for NetworkInput {
+        fn load(&self, day: u32) -> Result<String, MissingInput> {
+            OfflineInput::from_env().load(day).or_else(|missing| {
+                let question_day = day_of_number(day).ok_or(missing)?;
+                Ok(load_question_input(
+                    crate::YEAR,
+                    crate::COOKIE_PATH,
+                    crate::INPUT_CACHE,
+                    question_day,
+                ))
+            })
+        }
+    }
+}
+
+/// The provider used by the binary and the tests: offline unless the `network` feature is enabled.
+pub fn default_provider() -> Box<dyn InputProvider> {
+    #[cfg(feature = "network")]
+    return Box::new(NetworkInput);
+    #[cfg(not(feature = "network"))]
+    return Box::new(OfflineInput::from_env());
+}
+
+pub fn load_input(day: u32) -> Result<String, MissingInput> {
+    default_provider().load(day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_day_is_reported() {
+        let provider = OfflineInput::new("no/such/dir");
+        let error = provider.load(26).unwrap_err();
+        assert_eq!(error.day, 26);
+        assert_eq!(error.path, PathBuf::from("no/such/dir").join("26.in"));
+        assert!(error.to_string().starts_with("input missing for day 26"));
+    }
+
+    #[test]
+    fn reads_cached_input() {
+        let provider = OfflineInput::new(crate::INPUT_CACHE);
+        assert!(provider.load(1).is_ok());
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    time::{Duration, Instant},
+};
+
+use advent_of_code::parse::{parsers, Parser};
+
+use crate::{
+    input::{Field, InputError},
+    input_provider::MissingInput,
+    solution::{Answer, Solution},
+};
+
+pub const LEDGER_PATH: &str = "answers.toml";
+
+enum Entry<'a> {
+    Table(Field<'a>),
+    Answer(Field<'a>, String),
+}
+
+fn parse_entry(line: Field<'_>) -> Result<Entry<'_>, usize> {
+    if let Ok(table) = line.tag("[") {
+        let (name, rest) = table.split_once("]")?;
+        rest.empty()?;
+        return Ok(Entry::Table(name));
+    }
+    let (part, answer) = line.tag("part")?.split_once(" = ")?;
+    let (answer, rest) = answer.tag("\"")?.split_once("\"")?;
+    rest.empty()?;
+    Ok(Entry::Answer(part, answer.text().to_string()))
+}
+
+/// Parses the answer-file format shared by `answers.toml` and the example answer files: a small
+/// subset of TOML with `[name]` tables holding `part1` and `part2` as strings, plus `#` comments and
+/// blank lines. `table` turns a table name into its key, rejecting names it does not accept.
+pub fn parse_answer_tables<K: Ord + Clone>(
+    input: &str,
+    table_expected: &str,
+    table: impl Fn(&str) -> Option<K>,
+) -> Result<BTreeMap<(K, u32), String>, InputError> {
+    let mut answers = BTreeMap::new();
+    let mut key: Option<K> = None;
+    for (line_idx, line) in input.lines().enumerate() {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error =
+            |column: usize, expected: &str| InputError::at(input, line_idx + 1, column, expected);
+        let expected = format!("{} or a `partN = \"answer\"` entry", table_expected);
+        match parse_entry(Field::new(line)).map_err(|column| error(column, &expected))? {
+            Entry::Table(name) => {
+                key = Some(table(name.text()).ok_or_else(|| error(name.column(), table_expected))?)
+            }
+            Entry::Answer(part, answer) => {
+                let key = key
+                    .clone()
+                    .ok_or_else(|| error(1, &format!("{} before any answer", table_expected)))?;
+                let part = part
+                    .parse(parsers::tag_replace("1", 1).or(parsers::tag_replace("2", 2)))
+                    .map_err(|column| error(column, "`part1` or `part2`"))?;
+                if answers.insert((key, part), answer).is_some() {
+                    return Err(error(1, "each part to be recorded only once"));
+                }
+            }
+        }
+    }
+    Ok(answers)
+}
+
+/// The known-good answer for each day and part, as recorded in `answers.toml` with one `[day_NN]`
+/// table per day. A part with no entry has no confirmed answer yet.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Ledger {
+    answers: BTreeMap<(u32, u32), String>,
+}
+
+impl Ledger {
+    pub fn parse(input: &str) -> Result<Self, InputError> {
+        let answers = parse_answer_tables(input, "a `[day_NN]` header", |name| {
+            parsers::tag("day_")
+                .ignore_and_then(parsers::number())
+                .parse(name)
+                .finish()
+                .ok()
+        })?;
+        Ok(Ledger { answers })
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| format!("failed to read {}: {}", path, err))?;
+        Self::parse(&contents).map_err(|err| format!("{}: {}", path, err))
+    }
+
+    pub fn expected(&self, day: u32, part: u32) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (u32, u32, &str)> {
+        self.answers
+            .iter()
+            .map(|(&(day, part), answer)| (day, part, answer.as_str()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// The solution ran but the ledger has no answer to compare against.
+    Missing,
+    Error(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub day: u32,
+    pub part: u32,
+    pub answer: Option<Answer>,
+    pub elapsed: Duration,
+    pub status: Status,
+}
+
+/// Runs both parts of `solution` on `input` and compares each answer with the ledger.
+pub fn check_day(ledger: &Ledger, solution: &dyn Solution, input: &str) -> Vec<Check> {
+    let day = solution.day();
+    [1, 2]
+        .into_iter()
+        .map(|part| {
+            let start = Instant::now();
+            let result = solution.part(part, input).unwrap();
+            let elapsed = start.elapsed();
+            let (answer, status) = match (result, ledger.expected(day, part)) {
+                (Err(err), _) => (None, Status::Error(err.to_string())),
+                (Ok(answer), None) => (Some(answer), Status::Missing),
+                (Ok(answer), Some(expected)) if answer.to_string() == expected => {
+                    (Some(answer), Status::Pass)
+                }
+                (Ok(answer), Some(expected)) => (
+                    Some(answer),
+                    Status::Fail {
+                        expected: expected.to_string(),
+                    },
+                ),
+            };
+            Check {
+                day,
+                part,
+                answer,
+                elapsed,
+                status,
+            }
+        })
+        .collect()
+}
+
+/// The report for a day whose input could not be loaded, so neither part could be checked.
+pub fn missing_input(day: u32, err: &MissingInput) -> String {
+    format!("day {:02}        error    {}", day, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{input_provider::load_input, solution};
+
+    #[test]
+    fn parses_entries() {
+        let ledger = Ledger::parse(
+            "# comment
+
+[day_05]
+part1 = \"175622908\"
+part2 = \"5200543\"
+
+[day_10]
+part1 = \"6754\"
+",
+        )
+        .unwrap();
+        assert_eq!(ledger.expected(5, 2), Some("5200543"));
+        assert_eq!(ledger.expected(10, 1), Some("6754"));
+        assert_eq!(ledger.expected(10, 2), None);
+    }
+
+    #[test]
+    fn rejects_malformed_entries() {
+        let error = Ledger::parse("part1 = \"1\"\n").unwrap_err();
+        assert_eq!(error.line, 1);
+        let error = Ledger::parse("[day_x]\npart1 = \"1\"\n").unwrap_err();
+        assert_eq!(error.expected, "a `[day_NN]` header");
+        assert_eq!((error.line, error.column), (1, 2));
+        let error = Ledger::parse("[day_01]\npart1 = 1\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 9));
+        let error = Ledger::parse("[day_01]\npart3 = \"1\"\n").unwrap_err();
+        assert_eq!(error.expected, "`part1` or `part2`");
+        assert_eq!((error.line, error.column), (2, 5));
+        let error = Ledger::parse("[day_01]\npart1 = \"1\"\npart1 = \"2\"\n").unwrap_err();
+        assert_eq!(error.line, 3);
+    }
+
+    #[test]
+    fn ledger_days_are_registered() {
+        let ledger = Ledger::load(LEDGER_PATH).unwrap();
+        for (day, part, _) in ledger.entries() {
+            assert!(
+                solution::find(day).is_some(),
+                "ledger has an answer for day {} part {} but no solution is registered",
+                day,
+                part
+            );
+        }
+    }
+
+    /// Checks a day against the ledger on its cached input; a part without a recorded answer only
+    /// has to run without an input error.
+    fn check_ledger_day(day: u32) {
+        let solution = solution::find(day).unwrap();
+        let ledger = Ledger::load(LEDGER_PATH).unwrap();
+        let input = match load_input(day) {
+            Ok(input) => input,
+            Err(err) => panic!("{}", missing_input(day, &err)),
+        };
+        let failures: Vec<String> = check_day(&ledger, solution, &input)
+            .into_iter()
+            .filter_map(|check| match check.status {
+                Status::Pass | Status::Missing => None,
+                Status::Fail { expected } => Some(format!(
+                    "part {}: expected {}, got {}",
+                    check.part,
+                    expected,
+                    check.answer.unwrap()
+                )),
+                Status::Error(err) => Some(format!("part {}: {}", check.part, err)),
+            })
+            .collect();
+        assert!(failures.is_empty(), "day {}: {}", day, failures.join("; "));
+    }
+
+    /// One test per registered day, so that every regressed day is reported by name. `scaffold`
+    /// adds each new day's line.
+    macro_rules! ledger_tests {
+        ($($name:ident: $day:literal,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    check_ledger_day($day);
+                }
+            )*
+        };
+    }
+
+    ledger_tests! {
+        day_01_matches_ledger: 1,
+        day_02_matches_ledger: 2,
+        day_03_matches_ledger: 3,
+        day_04_matches_ledger: 4,
+        day_05_matches_ledger: 5,
+        day_06_matches_ledger: 6,
+        day_07_matches_ledger: 7,
+        day_08_matches_ledger: 8,
+        day_09_matches_ledger: 9,
+        day_10_matches_ledger: 10,
+        day_11_matches_ledger: 11,
+        day_12_matches_ledger: 12,
+        day_13_matches_ledger: 13,
+        day_14_matches_ledger: 14,
+        day_15_matches_ledger: 15,
+        day_16_matches_ledger: 16,
+        day_17_matches_ledger: 17,
+    }
+}
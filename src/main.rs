@@ -1,5 +1,5 @@
-#![feature(test)]
-
+mod bench;
+mod cli;
 mod day_01;
 mod day_02;
 mod day_03;
@@ -17,16 +17,300 @@ mod day_14;
 mod day_15;
 mod day_16;
 mod day_17;
+mod examples;
+mod input;
+mod input_provider;
+mod ledger;
+mod nonogram;
+mod pathfinding;
+mod report;
+mod scaffold;
+mod solution;
+#[cfg(test)]
+mod test_rng;
+
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+    process::ExitCode,
+};
+
+use bench::Stats;
+use cli::{Command, Format, InputSource, Selection};
+use ledger::{Ledger, Status, LEDGER_PATH};
+use solution::{Solution, SOLUTIONS};
 
-#[allow(dead_code)]
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "network")]
 const YEAR: &str = "2023";
 
-#[allow(dead_code)]
+#[cfg(feature = "network")]
 const COOKIE_PATH: &str = "../session.cookie";
 
-#[allow(dead_code)]
 const INPUT_CACHE: &str = "input";
 
-fn main() {
-    println!("Hello, world!");
+fn read_input(day: u32, source: &InputSource) -> Result<String, String> {
+    match source {
+        InputSource::Cache => input_provider::load_input(day).map_err(|err| err.to_string()),
+        InputSource::Example(name) => examples::load_example(day, name),
+        InputSource::File(path) => fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {}: {}", path.display(), err)),
+        InputSource::Stdin => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|err| format!("failed to read stdin: {}", err))?;
+            Ok(input)
+        }
+    }
+}
+
+/// Solves the given parts of one day, writing what should be printed to `out` so that days run
+/// concurrently can still be printed in order.
+fn run_day(
+    solution: &dyn Solution,
+    parts: &[u32],
+    source: &InputSource,
+    out: &mut String,
+) -> Result<(), String> {
+    let input = read_input(solution.day(), source)?;
+    let expected = match source {
+        InputSource::Example(_) => examples::expected_answers(solution.day())?,
+        _ => Default::default(),
+    };
+    out.push_str(&format!("day {:02}: {}\n", solution.day(), solution.name()));
+    for &part in parts {
+        let answer = solution
+            .part(part, &input)
+            .ok_or_else(|| format!("day {} has no part {}", solution.day(), part))?
+            .map_err(|err| format!("day {} part {}: {}", solution.day(), part, err))?;
+        let line = match source {
+            InputSource::Example(name) => match expected.get(&(name.clone(), part)) {
+                Some(expected) => format!("  part {}: {} (expected {})", part, answer, expected),
+                None => format!("  part {}: {} (no expected answer)", part, answer),
+            },
+            _ => format!("  part {}: {}", part, answer),
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+    Ok(())
+}
+
+/// Maps `f` over the days in `days`, on the rayon thread pool when the `parallel` feature is
+/// enabled. The results keep the order of `days` either way.
+fn map_days<T: Sync, R: Send>(days: &[T], f: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+    #[cfg(feature = "parallel")]
+    return days.par_iter().map(f).collect();
+    #[cfg(not(feature = "parallel"))]
+    return days.iter().map(f).collect();
+}
+
+fn run(selection: Selection, source: InputSource) -> Result<(), String> {
+    let days: Vec<(&dyn Solution, Vec<u32>)> = match selection {
+        Selection::Day(day, part) => {
+            let solution =
+                solution::find(day).ok_or_else(|| format!("day {} is not implemented", day))?;
+            vec![(solution, part.map_or(vec![1, 2], |part| vec![part]))]
+        }
+        Selection::All => SOLUTIONS
+            .iter()
+            .map(|&solution| (solution, vec![1, 2]))
+            .collect(),
+    };
+    let outputs = map_days(&days, |(solution, parts)| {
+        let mut out = String::new();
+        let result = run_day(*solution, parts, &source, &mut out);
+        (out, result)
+    });
+    for (out, result) in outputs {
+        print!("{}", out);
+        result?;
+    }
+    Ok(())
+}
+
+/// Runs the selection and prints one record per day and part. Unlike the text output this never
+/// stops early: failures are reported in the records and turn the exit status into a failure.
+fn run_structured(selection: Selection, source: InputSource, format: Format) -> Result<(), String> {
+    let days: Vec<(u32, Vec<u32>)> = match selection {
+        Selection::Day(day, Some(part)) => vec![(day, vec![part])],
+        Selection::Day(day, None) => vec![(day, vec![1, 2])],
+        Selection::All => (1..=report::LAST_DAY)
+            .map(|day| (day, vec![1, 2]))
+            .collect(),
+    };
+    let records: Vec<report::Record> = map_days(&days, |(day, parts)| {
+        let input = match solution::find(*day) {
+            Some(_) => read_input(*day, &source),
+            None => Err(format!("day {} is not implemented", day)),
+        };
+        report::records(*day, parts, input)
+    })
+    .into_iter()
+    .flatten()
+    .collect();
+    match format {
+        Format::Json => println!("{}", report::to_json(&records)),
+        _ => print!("{}", report::to_csv(&records)),
+    }
+    let errors = records
+        .iter()
+        .filter(|record| record.status == report::Status::Error)
+        .count();
+    if errors == 0 {
+        Ok(())
+    } else {
+        Err(format!("{} part(s) failed", errors))
+    }
+}
+
+/// Runs every registered solution on its cached input and compares the answers with the ledger,
+/// then runs the examples. Parts without a recorded answer are reported as missing but do not fail
+/// the run.
+fn verify() -> Result<(), String> {
+    let ledger = Ledger::load(LEDGER_PATH)?;
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for solution in SOLUTIONS {
+        let day = solution.day();
+        let input = match input_provider::load_input(day) {
+            Ok(input) => input,
+            Err(err) => {
+                println!("{}", ledger::missing_input(day, &err));
+                failed += 2;
+                continue;
+            }
+        };
+        for check in ledger::check_day(&ledger, *solution, &input) {
+            let answer = check
+                .answer
+                .map(|answer| answer.to_string())
+                .unwrap_or_default();
+            let outcome = match check.status {
+                Status::Pass => {
+                    passed += 1;
+                    format!("pass     {}", answer)
+                }
+                Status::Fail { expected } => {
+                    failed += 1;
+                    format!("FAIL     {} (expected {})", answer, expected)
+                }
+                Status::Missing => {
+                    missing += 1;
+                    format!("missing  {} (no recorded answer)", answer)
+                }
+                Status::Error(err) => {
+                    failed += 1;
+                    format!("error    {}", err)
+                }
+            };
+            println!(
+                "day {:02} part {}  {:>10.2?}  {}",
+                check.day, check.part, check.elapsed, outcome
+            );
+        }
+    }
+    for solution in SOLUTIONS {
+        for failure in examples::check_examples(*solution)? {
+            println!("{}", failure);
+            failed += 1;
+        }
+    }
+    for (day, part, _) in ledger.entries() {
+        if solution::find(day).is_none() {
+            println!(
+                "day {:02} part {}  recorded in {} but not registered",
+                day, part, LEDGER_PATH
+            );
+            failed += 1;
+        }
+    }
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(format!("{} part(s) did not match {}", failed, LEDGER_PATH))
+    }
+}
+
+fn print_stats(phase: &str, stats: &Stats) {
+    println!(
+        "    {:<6} min {:>10.2?}  median {:>10.2?}  mean {:>10.2?}  stddev {:>10.2?}",
+        phase, stats.min, stats.median, stats.mean, stats.stddev
+    );
+}
+
+fn run_bench(day: u32, part: Option<u32>, iterations: u32, warmups: u32) -> Result<(), String> {
+    let solution = solution::find(day).ok_or_else(|| format!("day {} is not implemented", day))?;
+    let input = input_provider::load_input(day).map_err(|err| err.to_string())?;
+    println!(
+        "day {:02}: {} ({} iterations after {} warmups)",
+        day,
+        solution.name(),
+        iterations,
+        warmups
+    );
+    let parts = match part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+    for part in parts {
+        let timings = bench::bench_part(solution, part, &input, iterations, warmups)
+            .map_err(|err| format!("day {} part {}: {}", day, part, err))?;
+        println!("  part {}:", part);
+        print_stats("total", &timings.total);
+        match (timings.parse, timings.solve) {
+            (Some(parse), Some(solve)) => {
+                print_stats("parse", &parse);
+                print_stats("solve", &solve);
+            }
+            _ => println!("    (no separate parse phase)"),
+        }
+    }
+    Ok(())
+}
+
+fn run_scaffold(day: u32) -> Result<(), String> {
+    for path in scaffold::scaffold(Path::new("."), day)? {
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run {
+            selection,
+            input,
+            format: Format::Text,
+        }) => run(selection, input),
+        Ok(Command::Run {
+            selection,
+            input,
+            format,
+        }) => run_structured(selection, input, format),
+        Ok(Command::Verify) => verify(),
+        Ok(Command::Bench {
+            day,
+            part,
+            iterations,
+            warmups,
+        }) => run_bench(day, part, iterations, warmups),
+        Ok(Command::Scaffold { day }) => run_scaffold(day),
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        }
+        Err(err) => Err(format!("{}\n{}", err, cli::USAGE)),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,278 @@
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt::{self, Display},
+};
+
+use advent_of_code::grid::{Grid, GridPoint};
+
+use crate::day_12::{Record, Spring};
+
+/// A row or column of a nonogram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line {
+    Row(usize),
+    Column(usize),
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Row(row) => write!(f, "row {}", row),
+            Self::Column(col) => write!(f, "column {}", col),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NonogramError {
+    /// There is not one clue per row, or one per column.
+    ClueCount { rows: usize, columns: usize },
+    /// No arrangement of `line` fits its clue and the cells already known. `guesses` is how many
+    /// cells had been guessed when it was found; 0 means the puzzle as given has no solution.
+    Contradiction { line: Line, guesses: usize },
+}
+
+impl Display for NonogramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ClueCount { rows, columns } => write!(
+                f,
+                "expected {} row clues and {} column clues",
+                rows, columns
+            ),
+            Self::Contradiction { line, guesses } => write!(
+                f,
+                "no arrangement of {} fits its clue after {} guesses",
+                line, guesses
+            ),
+        }
+    }
+}
+
+impl Error for NonogramError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solved {
+    Unique(Grid<Spring>),
+    /// Two of the solutions; there may be more.
+    Multiple(Grid<Spring>, Grid<Spring>),
+}
+
+struct Clues<'a> {
+    rows: &'a [Vec<usize>],
+    columns: &'a [Vec<usize>],
+}
+
+impl Clues<'_> {
+    fn points(&self, line: Line) -> Vec<GridPoint<usize>> {
+        match line {
+            Line::Row(row) => (0..self.columns.len())
+                .map(|col| GridPoint::new(row, col))
+                .collect(),
+            Line::Column(col) => (0..self.rows.len())
+                .map(|row| GridPoint::new(row, col))
+                .collect(),
+        }
+    }
+
+    fn clue(&self, line: Line) -> &[usize] {
+        match line {
+            Line::Row(row) => &self.rows[row],
+            Line::Column(col) => &self.columns[col],
+        }
+    }
+
+    fn fits(&self, line: Line, springs: Vec<Spring>) -> bool {
+        Record::new(springs, self.clue(line).to_vec())
+            .count_arrangements::<bool>()
+            .unwrap()
+    }
+
+    /// Fills in every cell of `line` that has the same state in all of its arrangements, returning
+    /// the cells that changed, or `None` if the line has no arrangement.
+    fn solve_line(&self, grid: &mut Grid<Spring>, line: Line) -> Option<Vec<GridPoint<usize>>> {
+        let points = self.points(line);
+        let mut springs: Vec<Spring> = points
+            .iter()
+            .map(|&point| *grid.get(point).unwrap())
+            .collect();
+        if !self.fits(line, springs.clone()) {
+            return None;
+        }
+        let mut changed = vec![];
+        for idx in 0..springs.len() {
+            if springs[idx] != Spring::Unknown {
+                continue;
+            }
+            for (tried, other) in [
+                (Spring::Broken, Spring::Working),
+                (Spring::Working, Spring::Broken),
+            ] {
+                springs[idx] = tried;
+                let fits = self.fits(line, springs.clone());
+                springs[idx] = Spring::Unknown;
+                if !fits {
+                    springs[idx] = other;
+                    grid.set(points[idx], other).unwrap();
+                    changed.push(points[idx]);
+                    break;
+                }
+            }
+        }
+        Some(changed)
+    }
+
+    /// Solves lines until none of them can fill in another cell.
+    fn propagate(&self, grid: &mut Grid<Spring>, mut to_solve: VecDeque<Line>) -> Result<(), Line> {
+        let mut queued: Vec<Line> = to_solve.iter().copied().collect();
+        while let Some(line) = to_solve.pop_front() {
+            queued.retain(|&queued| queued != line);
+            for point in self.solve_line(grid, line).ok_or(line)? {
+                let crossing = match line {
+                    Line::Row(_) => Line::Column(point.col()),
+                    Line::Column(_) => Line::Row(point.row()),
+                };
+                if !queued.contains(&crossing) {
+                    queued.push(crossing);
+                    to_solve.push_back(crossing);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Propagates, then guesses the first unknown cell each way, collecting up to two solutions.
+    fn search(
+        &self,
+        mut grid: Grid<Spring>,
+        to_solve: VecDeque<Line>,
+        guesses: usize,
+        solutions: &mut Vec<Grid<Spring>>,
+    ) -> Result<(), NonogramError> {
+        self.propagate(&mut grid, to_solve)
+            .map_err(|line| NonogramError::Contradiction { line, guesses })?;
+        let Some(unknown) = grid
+            .iter_points()
+            .find(|&point| grid.get(point).unwrap() == &Spring::Unknown)
+        else {
+            solutions.push(grid);
+            return Ok(());
+        };
+        let mut first_error = None;
+        for guess in [Spring::Broken, Spring::Working] {
+            let mut guessed = grid.clone();
+            guessed.set(unknown, guess).unwrap();
+            let lines = VecDeque::from([Line::Row(unknown.row()), Line::Column(unknown.col())]);
+            if let Err(err) = self.search(guessed, lines, guesses + 1, solutions) {
+                first_error.get_or_insert(err);
+            }
+            if solutions.len() >= 2 {
+                break;
+            }
+        }
+        match first_error {
+            Some(err) if solutions.is_empty() => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Solves a nonogram whose cells are springs: every row and column must have groups of broken
+/// springs of the lengths in its clue, in order. Cells of `grid` that are already known are kept.
+/// Each line is narrowed by the day 12 arrangement counter until nothing changes, then unknown
+/// cells are guessed.
+#[allow(dead_code)]
+pub fn solve(
+    grid: Grid<Spring>,
+    rows: &[Vec<usize>],
+    columns: &[Vec<usize>],
+) -> Result<Solved, NonogramError> {
+    if rows.len() != grid.rows() || columns.len() != grid.cols() {
+        return Err(NonogramError::ClueCount {
+            rows: grid.rows(),
+            columns: grid.cols(),
+        });
+    }
+    let clues = Clues { rows, columns };
+    let lines = (0..rows.len())
+        .map(Line::Row)
+        .chain((0..columns.len()).map(Line::Column))
+        .collect();
+    let mut solutions = vec![];
+    clues.search(grid, lines, 0, &mut solutions)?;
+    let mut solutions = solutions.into_iter();
+    match (solutions.next(), solutions.next()) {
+        (Some(first), None) => Ok(Solved::Unique(first)),
+        (Some(first), Some(second)) => Ok(Solved::Multiple(first, second)),
+        // The search only succeeds once it has found a solution.
+        (None, _) => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day_12::render;
+
+    fn unknown(rows: usize, cols: usize) -> Grid<Spring> {
+        Grid::of_vec_of_vecs(vec![vec![Spring::Unknown; cols]; rows]).unwrap()
+    }
+
+    fn rendered(grid: &Grid<Spring>) -> Vec<String> {
+        grid.all_rows().iter().map(|row| render(row)).collect()
+    }
+
+    #[test]
+    fn solves_by_propagation() {
+        let rows = [vec![1, 1], vec![5], vec![1, 1, 1], vec![3], vec![1]];
+        let columns = [vec![2], vec![2, 1], vec![4], vec![2, 1], vec![2]];
+        let Ok(Solved::Unique(grid)) = solve(unknown(5, 5), &rows, &columns) else {
+            panic!("expected a unique solution");
+        };
+        assert_eq!(
+            rendered(&grid),
+            vec![".#.#.", "#####", "#.#.#", ".###.", "..#.."]
+        );
+    }
+
+    #[test]
+    fn detects_multiple_solutions() {
+        let clue = [vec![1], vec![1]];
+        let Ok(Solved::Multiple(first, second)) = solve(unknown(2, 2), &clue, &clue) else {
+            panic!("expected two solutions");
+        };
+        assert_ne!(first, second);
+        let mut given = unknown(2, 2);
+        given.set(GridPoint::new(0, 0), Spring::Broken).unwrap();
+        let Ok(Solved::Unique(grid)) = solve(given, &clue, &clue) else {
+            panic!("expected a unique solution");
+        };
+        assert_eq!(rendered(&grid), vec!["#.", ".#"]);
+    }
+
+    #[test]
+    fn reports_contradictions() {
+        assert_eq!(
+            solve(unknown(2, 2), &[vec![2], vec![]], &[vec![1], vec![]]),
+            Err(NonogramError::Contradiction {
+                line: Line::Column(1),
+                guesses: 0
+            })
+        );
+        assert_eq!(
+            solve(unknown(1, 2), &[vec![3]], &[vec![1], vec![1]]),
+            Err(NonogramError::Contradiction {
+                line: Line::Row(0),
+                guesses: 0
+            })
+        );
+        assert_eq!(
+            solve(unknown(2, 2), &[vec![1]], &[vec![], vec![]]),
+            Err(NonogramError::ClueCount {
+                rows: 2,
+                columns: 2
+            })
+        );
+    }
+}
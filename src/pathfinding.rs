@@ -0,0 +1,448 @@
+use advent_of_code::grid::{Grid, GridPoint, GridPointDelta, PLUS_ADJACENT};
+
+/// How a mover may travel across a grid. The mover moves in straight segments; at the end of each
+/// segment it picks a new direction from [`directions`](MovementRules::directions) that
+/// [`can_turn`](MovementRules::can_turn) allows.
+pub trait MovementRules {
+    /// Every direction the mover can travel in. Include diagonals to allow diagonal movement.
+    fn directions(&self) -> &[GridPointDelta<isize>] {
+        &PLUS_ADJACENT
+    }
+
+    /// Whether a segment in `previous` may be followed by one in `next`. By default the mover must
+    /// change direction and may not double back unless [`allow_reverse`](Self::allow_reverse).
+    fn can_turn(&self, previous: GridPointDelta<isize>, next: GridPointDelta<isize>) -> bool {
+        next != previous && (self.allow_reverse() || next != -previous)
+    }
+
+    fn allow_reverse(&self) -> bool {
+        false
+    }
+
+    /// The fewest blocks a segment may cover.
+    fn min_segment(&self) -> usize {
+        1
+    }
+
+    /// The most blocks a segment may cover.
+    fn max_segment(&self) -> usize {
+        usize::MAX
+    }
+
+    /// A lower bound on the cost of getting from `from` to `to`, used to guide the search. It must
+    /// never overestimate; the default of 0 makes the search plain Dijkstra.
+    fn estimate(&self, _from: GridPoint<usize>, _to: GridPoint<usize>) -> u32 {
+        0
+    }
+}
+
+/// One straight run of a path. `cost` is the cost of the blocks entered, so it excludes `start` and
+/// includes `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    pub start: GridPoint<usize>,
+    pub end: GridPoint<usize>,
+    pub dir: GridPointDelta<isize>,
+    pub cost: u32,
+}
+
+#[allow(dead_code)]
+impl Segment {
+    /// The number of blocks entered.
+    pub fn length(&self) -> usize {
+        let delta: GridPointDelta<isize> = self.end.sub(self.start).unwrap();
+        delta
+            .row_delta()
+            .unsigned_abs()
+            .max(delta.col_delta().unsigned_abs())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    pub segments: Vec<Segment>,
+    pub cost: u32,
+}
+
+#[allow(dead_code)]
+impl Path {
+    /// Every block on the path, from the start to the target.
+    pub fn points(&self) -> Vec<GridPoint<usize>> {
+        let mut points: Vec<GridPoint<usize>> = self
+            .segments
+            .first()
+            .map(|segment| segment.start)
+            .into_iter()
+            .collect();
+        for segment in &self.segments {
+            let mut point = segment.start;
+            while point != segment.end {
+                point = (point + segment.dir).unwrap();
+                points.push(point);
+            }
+        }
+        points
+    }
+
+    /// The start, every block where the path turns, and the target.
+    pub fn turn_points(&self) -> Vec<GridPoint<usize>> {
+        self.segments
+            .first()
+            .map(|segment| segment.start)
+            .into_iter()
+            .chain(self.segments.iter().map(|segment| segment.end))
+            .collect()
+    }
+}
+
+/// A queue for small integer priorities (Dial's algorithm): one bucket per priority, drained in
+/// order. Priorities pushed below the one being drained are treated as the current priority.
+struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    current: usize,
+}
+
+impl<T> BucketQueue<T> {
+    fn new() -> Self {
+        Self {
+            buckets: Vec::new(),
+            current: 0,
+        }
+    }
+
+    fn push(&mut self, priority: u32, item: T) {
+        let priority = (priority as usize).max(self.current);
+        if priority >= self.buckets.len() {
+            self.buckets.resize_with(priority + 1, Vec::new);
+        }
+        self.buckets[priority].push(item);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        while self.current < self.buckets.len() {
+            if let Some(item) = self.buckets[self.current].pop() {
+                return Some(item);
+            }
+            self.current += 1;
+        }
+        None
+    }
+}
+
+/// Search states numbered densely so that the bookkeeping can live in flat vectors instead of hash
+/// maps. A state is a point and the class of directions the mover may leave it in. When the rules
+/// never tell a direction from its reverse (the default rules, or the King's), the two share a
+/// class, so a cell has one state per axis. Otherwise, e.g. when
+/// [`allow_reverse`](MovementRules::allow_reverse) lets a segment double back, reaching a cell
+/// heading east is not the same as heading west, and each direction is its own class.
+struct StateIndex {
+    cols: usize,
+    /// The class of each direction, by index into [`MovementRules::directions`].
+    class_of: Vec<usize>,
+    /// The directions in each class.
+    classes: Vec<Vec<usize>>,
+}
+
+impl StateIndex {
+    fn new(cols: usize, rules: &impl MovementRules) -> Self {
+        let directions = rules.directions();
+        let reverse = |dir: usize| {
+            directions
+                .iter()
+                .position(|&other| other == -directions[dir])
+        };
+        let by_axis = (0..directions.len()).all(|dir| {
+            reverse(dir).is_some_and(|reversed| {
+                directions.iter().all(|&next| {
+                    let turn = rules.can_turn(directions[dir], next);
+                    turn == rules.can_turn(directions[reversed], next)
+                        && turn == rules.can_turn(directions[dir], -next)
+                })
+            })
+        });
+        let mut class_of = vec![usize::MAX; directions.len()];
+        let mut classes: Vec<Vec<usize>> = Vec::new();
+        for dir in 0..directions.len() {
+            if class_of[dir] != usize::MAX {
+                continue;
+            }
+            let mut class = vec![dir];
+            if by_axis {
+                class.extend(reverse(dir).filter(|&reversed| reversed != dir));
+            }
+            for &member in &class {
+                class_of[member] = classes.len();
+            }
+            classes.push(class);
+        }
+        Self {
+            cols,
+            class_of,
+            classes,
+        }
+    }
+
+    fn of(&self, point: GridPoint<usize>, class: usize) -> usize {
+        (point.row() * self.cols + point.col()) * self.classes.len() + class
+    }
+
+    fn state(&self, index: usize) -> (GridPoint<usize>, usize) {
+        let cell = index / self.classes.len();
+        (
+            GridPoint::new(cell / self.cols, cell % self.cols),
+            index % self.classes.len(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SearchElement {
+    state: usize,
+    cost: u32,
+    /// The state the segment ending here started from, the direction it went in, and its cost.
+    previous: Option<(usize, usize, u32)>,
+}
+
+/// Finds the cheapest path from `start` to `target` under `rules`, where entering a block costs
+/// `cost(point, value)` and `None` marks a block that cannot be entered. Returns `None` if the
+/// target cannot be reached.
+pub fn find_path<T>(
+    grid: &Grid<T>,
+    rules: &impl MovementRules,
+    cost: impl Fn(GridPoint<usize>, &T) -> Option<u32>,
+    start: GridPoint<usize>,
+    target: GridPoint<usize>,
+) -> Option<Path> {
+    let grid_dimensions = grid.dimensions();
+    let directions = rules.directions();
+    let index = StateIndex::new(grid.cols(), rules);
+    let state_count = grid.rows() * grid.cols() * index.classes.len();
+    let mut to_search: BucketQueue<SearchElement> = BucketQueue::new();
+    let push = |to_search: &mut BucketQueue<SearchElement>, element: SearchElement| {
+        let point = index.state(element.state).0;
+        to_search.push(element.cost + rules.estimate(point, target), element);
+    };
+    let mut seen = vec![false; state_count];
+    let mut previous: Vec<Option<(usize, usize, u32)>> = vec![None; state_count];
+    for class in 0..index.classes.len() {
+        let state = index.of(start, class);
+        push(
+            &mut to_search,
+            SearchElement {
+                state,
+                cost: 0,
+                previous: None,
+            },
+        );
+    }
+    while let Some(next) = to_search.pop() {
+        if seen[next.state] {
+            continue;
+        }
+        seen[next.state] = true;
+        previous[next.state] = next.previous;
+        let (current, class) = index.state(next.state);
+        if current == target {
+            return Some(reconstruct(next, &previous, &index, directions));
+        }
+        for &dir_idx in &index.classes[class] {
+            let dir = directions[dir_idx];
+            let mut point = current;
+            let mut segment_cost = 0;
+            for length in 1..=rules.max_segment() {
+                let Some(stepped) = point.add_checked(dir, &grid_dimensions) else {
+                    break;
+                };
+                let Some(step_cost) = cost(stepped, grid.get(stepped).unwrap()) else {
+                    break;
+                };
+                point = stepped;
+                segment_cost += step_cost;
+                if length < rules.min_segment() {
+                    continue;
+                }
+                let element = |state: usize| SearchElement {
+                    state,
+                    cost: next.cost + segment_cost,
+                    previous: Some((next.state, dir_idx, segment_cost)),
+                };
+                for (next_class, members) in index.classes.iter().enumerate() {
+                    let state = index.of(point, next_class);
+                    if seen[state] {
+                        continue;
+                    }
+                    // Every direction in a class turns the same way, so any one stands for all.
+                    if rules.can_turn(dir, directions[members[0]])
+                        || (point == target && next_class == index.class_of[dir_idx])
+                    {
+                        push(&mut to_search, element(state));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+fn reconstruct(
+    last: SearchElement,
+    previous: &[Option<(usize, usize, u32)>],
+    index: &StateIndex,
+    directions: &[GridPointDelta<isize>],
+) -> Path {
+    let mut segments = Vec::new();
+    let mut end = index.state(last.state).0;
+    let mut step = last.previous;
+    while let Some((state, dir, cost)) = step {
+        let start = index.state(state).0;
+        segments.push(Segment {
+            start,
+            end,
+            dir: directions[dir],
+            cost,
+        });
+        end = start;
+        step = previous[state];
+    }
+    segments.reverse();
+    Path {
+        segments,
+        cost: last.cost,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use advent_of_code::grid::{ADJACENT, EAST, SOUTH};
+
+    use super::*;
+
+    /// Moves one block at a time in any direction, like a king on a chessboard.
+    struct King;
+
+    impl MovementRules for King {
+        fn directions(&self) -> &[GridPointDelta<isize>] {
+            &ADJACENT
+        }
+
+        fn can_turn(&self, _previous: GridPointDelta<isize>, _next: GridPointDelta<isize>) -> bool {
+            true
+        }
+
+        fn max_segment(&self) -> usize {
+            1
+        }
+    }
+
+    /// Walks straight as far as it likes and may reverse.
+    struct Rook;
+
+    impl MovementRules for Rook {
+        fn allow_reverse(&self) -> bool {
+            true
+        }
+    }
+
+    fn grid(rows: &[&str]) -> Grid<char> {
+        Grid::of_vec_of_vecs(rows.iter().map(|row| row.chars().collect()).collect()).unwrap()
+    }
+
+    fn open(_: GridPoint<usize>, &c: &char) -> Option<u32> {
+        (c != '#').then_some(1)
+    }
+
+    #[test]
+    fn bucket_queue_order() {
+        let mut queue = BucketQueue::new();
+        for (priority, item) in [(3, 'c'), (1, 'a'), (3, 'd'), (2, 'b')] {
+            queue.push(priority, item);
+        }
+        assert_eq!(queue.pop(), Some('a'));
+        queue.push(0, 'z');
+        assert_eq!(queue.pop(), Some('z'));
+        assert_eq!(queue.pop(), Some('b'));
+        let mut rest = vec![queue.pop().unwrap(), queue.pop().unwrap()];
+        rest.sort();
+        assert_eq!(rest, vec!['c', 'd']);
+        assert_eq!(queue.pop(), None);
+    }
+
+    /// The default rules: turn left or right after every block.
+    struct Walker;
+
+    impl MovementRules for Walker {}
+
+    #[test]
+    fn states_by_axis_when_rules_allow() {
+        assert_eq!(StateIndex::new(3, &Walker).classes.len(), 2);
+        assert_eq!(StateIndex::new(3, &King).classes.len(), 4);
+        assert_eq!(StateIndex::new(3, &Rook).classes.len(), 4);
+        let index = StateIndex::new(3, &Walker);
+        let point = GridPoint::new(2, 1);
+        assert_eq!(index.state(index.of(point, 1)), (point, 1));
+    }
+
+    #[test]
+    fn diagonal_movement() {
+        let grid = grid(&["...", "...", "..."]);
+        let path = find_path(
+            &grid,
+            &King,
+            open,
+            GridPoint::new(0, 0),
+            GridPoint::new(2, 2),
+        );
+        assert_eq!(path.map(|path| path.cost), Some(2));
+    }
+
+    #[test]
+    fn walls_and_segments() {
+        let grid = grid(&["....", "###.", "....", ".###"]);
+        let path = find_path(
+            &grid,
+            &Rook,
+            open,
+            GridPoint::new(0, 0),
+            GridPoint::new(3, 0),
+        )
+        .unwrap();
+        assert_eq!(path.cost, 9);
+        assert_eq!(
+            path.turn_points(),
+            vec![
+                GridPoint::new(0, 0),
+                GridPoint::new(0, 3),
+                GridPoint::new(2, 3),
+                GridPoint::new(2, 0),
+                GridPoint::new(3, 0),
+            ]
+        );
+        assert_eq!(path.segments[0].dir, EAST);
+        assert_eq!(path.segments[1].dir, SOUTH);
+        assert_eq!(path.segments[0].length(), 3);
+        assert_eq!(path.points().len(), 10);
+    }
+
+    #[test]
+    fn unreachable_target() {
+        let grid = grid(&[".#.", "##.", "..."]);
+        assert_eq!(
+            find_path(
+                &grid,
+                &Rook,
+                open,
+                GridPoint::new(0, 0),
+                GridPoint::new(2, 2)
+            ),
+            None
+        );
+        let path = find_path(
+            &grid,
+            &Rook,
+            open,
+            GridPoint::new(0, 0),
+            GridPoint::new(0, 0),
+        );
+        assert_eq!(path.map(|path| path.segments.len()), Some(0));
+    }
+}
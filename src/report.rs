@@ -0,0 +1,244 @@
+use std::{
+    fmt::{self, Display},
+    time::{Duration, Instant},
+};
+
+use crate::solution::{self, Answer};
+
+/// The last day of the event; structured reports for `--all` cover every day up to it.
+pub const LAST_DAY: u32 = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Error,
+    Unimplemented,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ok => write!(f, "ok"),
+            Self::Error => write!(f, "error"),
+            Self::Unimplemented => write!(f, "unimplemented"),
+        }
+    }
+}
+
+/// The outcome of one day and part, in the shape written by `--format json` and `--format csv`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u32,
+    pub part: u32,
+    pub status: Status,
+    pub answer: Option<Answer>,
+    pub elapsed: Duration,
+    pub input_hash: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// 64-bit FNV-1a, enough to tell which input file a result was computed from.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Runs the given parts of `day`, turning every failure into a record instead of stopping.
+pub fn records(day: u32, parts: &[u32], input: Result<String, String>) -> Vec<Record> {
+    let record = |part: u32, status: Status| Record {
+        day,
+        part,
+        status,
+        answer: None,
+        elapsed: Duration::ZERO,
+        input_hash: None,
+        error: None,
+    };
+    let Some(solution) = solution::find(day) else {
+        return parts
+            .iter()
+            .map(|&part| record(part, Status::Unimplemented))
+            .collect();
+    };
+    let input = match input {
+        Ok(input) => input,
+        Err(err) => {
+            return parts
+                .iter()
+                .map(|&part| Record {
+                    error: Some(err.clone()),
+                    ..record(part, Status::Error)
+                })
+                .collect()
+        }
+    };
+    let input_hash = Some(fnv1a(input.as_bytes()));
+    parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let result = solution.part(part, &input);
+            let elapsed = start.elapsed();
+            match result {
+                Some(Ok(answer)) => Record {
+                    answer: Some(answer),
+                    elapsed,
+                    input_hash,
+                    ..record(part, Status::Ok)
+                },
+                Some(Err(err)) => Record {
+                    elapsed,
+                    input_hash,
+                    error: Some(err.to_string()),
+                    ..record(part, Status::Error)
+                },
+                None => record(part, Status::Unimplemented),
+            }
+        })
+        .collect()
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn hash_string(hash: Option<u64>) -> Option<String> {
+    hash.map(|hash| format!("{:016x}", hash))
+}
+
+/// One JSON array of objects. Answers are strings so that large answers survive JSON parsers that
+/// read every number as a double.
+pub fn to_json(records: &[Record]) -> String {
+    let null_or = |value: Option<String>| value.unwrap_or_else(|| "null".to_string());
+    let objects: Vec<String> = records
+        .iter()
+        .map(|record| {
+            format!(
+                "  {{\"day\": {}, \"part\": {}, \"status\": \"{}\", \"answer\": {}, \"elapsed_us\": {}, \"input_hash\": {}, \"error\": {}}}",
+                record.day,
+                record.part,
+                record.status,
+                null_or(record.answer.map(|answer| json_string(&answer.to_string()))),
+                record.elapsed.as_micros(),
+                null_or(hash_string(record.input_hash).map(|hash| json_string(&hash))),
+                null_or(record.error.as_deref().map(json_string)),
+            )
+        })
+        .collect();
+    if objects.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n{}\n]", objects.join(",\n"))
+    }
+}
+
+/// A header row followed by one row per record; missing values are empty fields.
+pub fn to_csv(records: &[Record]) -> String {
+    let mut csv = String::from("day,part,status,answer,elapsed_us,input_hash,error\n");
+    for record in records {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            record.day,
+            record.part,
+            record.status,
+            record
+                .answer
+                .map(|answer| answer.to_string())
+                .unwrap_or_default(),
+            record.elapsed.as_micros(),
+            hash_string(record.input_hash).unwrap_or_default(),
+            csv_field(record.error.as_deref().unwrap_or("")),
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<Record> {
+        vec![
+            Record {
+                day: 1,
+                part: 2,
+                status: Status::Ok,
+                answer: Some(Answer::Unsigned(281)),
+                elapsed: Duration::from_micros(42),
+                input_hash: Some(0xaf63bd4c8601b7df),
+                error: None,
+            },
+            Record {
+                day: 2,
+                part: 1,
+                status: Status::Error,
+                answer: None,
+                elapsed: Duration::ZERO,
+                input_hash: None,
+                error: Some("line 1, column 1: expected a \"game\", found `x`".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn fnv1a_known_values() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn json_output() {
+        assert_eq!(
+            to_json(&example()),
+            r#"[
+  {"day": 1, "part": 2, "status": "ok", "answer": "281", "elapsed_us": 42, "input_hash": "af63bd4c8601b7df", "error": null},
+  {"day": 2, "part": 1, "status": "error", "answer": null, "elapsed_us": 0, "input_hash": null, "error": "line 1, column 1: expected a \"game\", found `x`"}
+]"#
+        );
+        assert_eq!(to_json(&[]), "[]");
+    }
+
+    #[test]
+    fn csv_output() {
+        assert_eq!(
+            to_csv(&example()),
+            "day,part,status,answer,elapsed_us,input_hash,error
+1,2,ok,281,42,af63bd4c8601b7df,
+2,1,error,,0,,\"line 1, column 1: expected a \"\"game\"\", found `x`\"
+"
+        );
+    }
+
+    #[test]
+    fn failures_become_records() {
+        let unimplemented = records(0, &[1, 2], Err("unused".to_string()));
+        assert!(unimplemented
+            .iter()
+            .all(|record| record.status == Status::Unimplemented));
+        let missing = records(1, &[1], Err("input missing for day 1".to_string()));
+        assert_eq!(missing[0].status, Status::Error);
+        let solved = records(1, &[1], Ok("1abc2\n".to_string()));
+        assert_eq!(solved[0].answer, Some(Answer::Unsigned(12)));
+        assert_eq!(solved[0].input_hash, Some(fnv1a(b"1abc2\n")));
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{examples, report::LAST_DAY};
+
+const TEMPLATE_PATH: &str = "src/day_template.rs";
+const MAIN_PATH: &str = "src/main.rs";
+const SOLUTION_PATH: &str = "src/solution.rs";
+const LEDGER_TESTS_PATH: &str = "src/ledger.rs";
+
+const EXAMPLE_ANSWERS: &str =
+    "# Expected answers for the examples in this directory, one table per `NAME.in`.
+# [example]
+# part1 = \"\"
+# part2 = \"\"
+";
+
+const TEMPLATE_DAY: &str = "    fn day(&self) -> u32 {\n        1\n    }";
+
+/// Fills in the template for `day`: the solution struct name and the day number.
+fn render_module(template: &str, day: u32) -> Result<String, String> {
+    if !template.contains(TEMPLATE_DAY) || !template.contains("Day01") {
+        return Err(format!(
+            "{} no longer defines `Day01` with `fn day` returning 1",
+            TEMPLATE_PATH
+        ));
+    }
+    Ok(template
+        .replace(
+            TEMPLATE_DAY,
+            &TEMPLATE_DAY.replace("        1\n", &format!("        {}\n", day)),
+        )
+        .replace("Day01", &format!("Day{:02}", day)))
+}
+
+/// Inserts `line` after the last line that `entry_day` recognizes and whose number is below `day`, or
+/// before the first entry if there is none, keeping the entries sorted.
+fn insert_sorted(
+    source: &str,
+    day: u32,
+    line: &str,
+    entry_day: impl Fn(&str) -> Option<u32>,
+    what: &str,
+) -> Result<String, String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let entries: Vec<(usize, u32)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| entry_day(line).map(|entry| (idx, entry)))
+        .collect();
+    if entries.iter().any(|&(_, entry)| entry == day) {
+        return Err(format!("day {} is already registered in {}", day, what));
+    }
+    let position = match entries.iter().rev().find(|&&(_, entry)| entry < day) {
+        Some(&(idx, _)) => idx + 1,
+        None => entries
+            .first()
+            .map(|&(idx, _)| idx)
+            .ok_or_else(|| format!("found no day entries to extend in {}", what))?,
+    };
+    let mut updated: Vec<&str> = lines[..position].to_vec();
+    updated.push(line);
+    updated.extend(&lines[position..]);
+    Ok(updated.join("\n") + "\n")
+}
+
+fn day_number(digits: &str) -> Option<u32> {
+    (digits.len() == 2).then(|| digits.parse().ok()).flatten()
+}
+
+fn register_module(main: &str, day: u32) -> Result<String, String> {
+    insert_sorted(
+        main,
+        day,
+        &format!("mod day_{:02};", day),
+        |line| day_number(line.strip_prefix("mod day_")?.strip_suffix(';')?),
+        MAIN_PATH,
+    )
+}
+
+fn register_solution(solution: &str, day: u32) -> Result<String, String> {
+    insert_sorted(
+        solution,
+        day,
+        &format!("    &crate::day_{:02}::Day{:02},", day, day),
+        |line| day_number(line.strip_prefix("    &crate::day_")?.get(..2)?),
+        SOLUTION_PATH,
+    )
+}
+
+fn register_ledger_test(ledger: &str, day: u32) -> Result<String, String> {
+    insert_sorted(
+        ledger,
+        day,
+        &format!("        day_{:02}_matches_ledger: {},", day, day),
+        |line| {
+            let rest = line.strip_prefix("        day_")?;
+            rest.get(2..)?
+                .starts_with("_matches_ledger: ")
+                .then_some(())?;
+            day_number(rest.get(..2)?)
+        },
+        LEDGER_TESTS_PATH,
+    )
+}
+
+fn write_new(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|err| format!("failed to create {}: {}", dir.display(), err))?;
+    }
+    fs::write(path, contents).map_err(|err| format!("failed to write {}: {}", path.display(), err))
+}
+
+/// Generates `src/day_NN.rs` under `root` and registers it, along with its ledger test, plus
+/// placeholder input and example files.
+/// Nothing is written if the day already exists. Returns the files that were created or changed.
+pub fn scaffold(root: &Path, day: u32) -> Result<Vec<PathBuf>, String> {
+    if !(1..=LAST_DAY).contains(&day) {
+        return Err(format!(
+            "day must be between 1 and {}, got {}",
+            LAST_DAY, day
+        ));
+    }
+    let read = |path: &str| {
+        fs::read_to_string(root.join(path))
+            .map_err(|err| format!("failed to read {}: {}", root.join(path).display(), err))
+    };
+    let module_path = root.join(format!("src/day_{:02}.rs", day));
+    if module_path.exists() {
+        return Err(format!("{} already exists", module_path.display()));
+    }
+    let module = render_module(&read(TEMPLATE_PATH)?, day)?;
+    let main = register_module(&read(MAIN_PATH)?, day)?;
+    let solution = register_solution(&read(SOLUTION_PATH)?, day)?;
+    let ledger_tests = register_ledger_test(&read(LEDGER_TESTS_PATH)?, day)?;
+
+    let mut written = vec![
+        module_path.clone(),
+        root.join(MAIN_PATH),
+        root.join(SOLUTION_PATH),
+        root.join(LEDGER_TESTS_PATH),
+    ];
+    write_new(&module_path, &module)?;
+    write_new(&root.join(MAIN_PATH), &main)?;
+    write_new(&root.join(SOLUTION_PATH), &solution)?;
+    write_new(&root.join(LEDGER_TESTS_PATH), &ledger_tests)?;
+    let example_dir = root.join(examples::example_dir(day));
+    let placeholders = [
+        (
+            root.join(crate::INPUT_CACHE).join(format!("{}.in", day)),
+            "",
+        ),
+        (example_dir.join("example.in"), ""),
+        (example_dir.join(examples::ANSWERS_FILE), EXAMPLE_ANSWERS),
+    ];
+    for (path, contents) in placeholders {
+        if !path.exists() {
+            write_new(&path, contents)?;
+            written.push(path);
+        }
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::ledger;
+
+    #[test]
+    fn renders_template() {
+        let template = fs::read_to_string(TEMPLATE_PATH).unwrap();
+        let module = render_module(&template, 18).unwrap();
+        assert!(module.contains("pub struct Day18;"));
+        assert!(module.contains("impl Solution for Day18"));
+        assert!(module.contains("    fn day(&self) -> u32 {\n        18\n    }"));
+        assert!(!module.contains("Day01"));
+    }
+
+    #[test]
+    fn registers_in_order() {
+        let main = "mod cli;\nmod day_01;\nmod day_03;\nmod input;\n";
+        assert_eq!(
+            register_module(main, 2),
+            Ok("mod cli;\nmod day_01;\nmod day_02;\nmod day_03;\nmod input;\n".to_string())
+        );
+        assert_eq!(
+            register_module(main, 4),
+            Ok("mod cli;\nmod day_01;\nmod day_03;\nmod day_04;\nmod input;\n".to_string())
+        );
+        assert!(register_module(main, 3).is_err());
+
+        let solution =
+            "pub static SOLUTIONS: &[&dyn Solution] = &[\n    &crate::day_17::Day17,\n];\n";
+        assert_eq!(
+            register_solution(solution, 18),
+            Ok("pub static SOLUTIONS: &[&dyn Solution] = &[\n    &crate::day_17::Day17,\n    &crate::day_18::Day18,\n];\n".to_string())
+        );
+
+        let ledger = "    ledger_tests! {\n        day_01_matches_ledger: 1,\n    }\n";
+        assert_eq!(
+            register_ledger_test(ledger, 2),
+            Ok("    ledger_tests! {\n        day_01_matches_ledger: 1,\n        day_02_matches_ledger: 2,\n    }\n".to_string())
+        );
+        assert!(register_ledger_test(ledger, 1).is_err());
+    }
+
+    #[test]
+    fn scaffolds_and_refuses_to_overwrite() {
+        let root = env::temp_dir().join(format!("aoc_scaffold_{}", std::process::id()));
+        for path in [TEMPLATE_PATH, MAIN_PATH, SOLUTION_PATH, LEDGER_TESTS_PATH] {
+            write_new(&root.join(path), &fs::read_to_string(path).unwrap()).unwrap();
+        }
+        let written = scaffold(&root, 25).unwrap();
+        assert_eq!(written.len(), 7);
+        assert!(fs::read_to_string(root.join("src/main.rs"))
+            .unwrap()
+            .contains("mod day_25;"));
+        assert!(fs::read_to_string(root.join("src/ledger.rs"))
+            .unwrap()
+            .contains("        day_17_matches_ledger: 17,\n        day_25_matches_ledger: 25,\n"));
+        assert!(root.join("input/25.in").exists());
+        assert!(root.join("examples/25/example.in").exists());
+        let answers = fs::read_to_string(root.join("examples/25/answers.toml")).unwrap();
+        assert!(ledger::parse_answer_tables(&answers, "a table", |_| Some(())).is_ok());
+        assert!(scaffold(&root, 25).unwrap_err().contains("already exists"));
+        assert!(scaffold(&root, 26).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
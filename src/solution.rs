@@ -0,0 +1,112 @@
+use std::fmt::{self, Display};
+
+use crate::input::InputError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsigned(n) => write!(f, "{}", n),
+            Self::Signed(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Self {
+        Self::Unsigned(n as u64)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Self {
+        Self::Unsigned(n)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Self::Unsigned(n as u64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Self::Signed(n)
+    }
+}
+
+pub trait Solution: Sync {
+    fn name(&self) -> &'static str;
+    fn day(&self) -> u32;
+    fn part1(&self, input: &str) -> Result<Answer, InputError>;
+    fn part2(&self, input: &str) -> Result<Answer, InputError>;
+
+    /// Runs only the parsing that `part` starts with, so the bench command can time it apart from
+    /// the solve. Days that do not parse up front return `None`.
+    fn parse(&self, _part: u32, _input: &str) -> Option<Result<(), InputError>> {
+        None
+    }
+
+    fn part(&self, part: u32, input: &str) -> Option<Result<Answer, InputError>> {
+        match part {
+            1 => Some(self.part1(input)),
+            2 => Some(self.part2(input)),
+            _ => None,
+        }
+    }
+}
+
+pub static SOLUTIONS: &[&dyn Solution] = &[
+    &crate::day_01::Day01,
+    &crate::day_02::Day02,
+    &crate::day_03::Day03,
+    &crate::day_04::Day04,
+    &crate::day_05::Day05,
+    &crate::day_06::Day06,
+    &crate::day_07::Day07,
+    &crate::day_08::Day08,
+    &crate::day_09::Day09,
+    &crate::day_10::Day10,
+    &crate::day_11::Day11,
+    &crate::day_12::Day12,
+    &crate::day_13::Day13,
+    &crate::day_14::Day14,
+    &crate::day_15::Day15,
+    &crate::day_16::Day16,
+    &crate::day_17::Day17,
+];
+
+pub fn find(day: u32) -> Option<&'static dyn Solution> {
+    SOLUTIONS
+        .iter()
+        .copied()
+        .find(|solution| solution.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_ordered_by_day() {
+        for (idx, solution) in SOLUTIONS.iter().enumerate() {
+            assert_eq!(solution.day(), idx as u32 + 1, "{}", solution.name());
+        }
+    }
+
+    #[test]
+    fn find_by_day() {
+        assert_eq!(
+            find(17).map(|solution| solution.name()),
+            Some("Clumsy Crucible")
+        );
+        assert!(find(0).is_none());
+        assert!(find(26).is_none());
+    }
+}